@@ -0,0 +1,86 @@
+use crate::commands::error::CommandError;
+use crate::load;
+use crate::load::diff;
+use crate::load::error::LoadError;
+use crate::load::lockfile::{self, Lockfile};
+use crate::load::copy::CopyOptions;
+use crate::load::plan::{self, Action};
+use crate::prompt::Prompter;
+use crate::registry::{Entry, Registry};
+use crate::task::answers::Answers;
+use clap::ArgMatches;
+use std::env;
+use std::io::{self, IsTerminal};
+use std::path::PathBuf;
+
+pub fn run(matches: &ArgMatches) -> Result<(), CommandError> {
+    let current_dir = env::current_dir().expect("Get current directory");
+
+    let query = matches
+        .get_one::<String>("name")
+        .expect("Get name argument");
+    let registry = Registry::read()?;
+
+    let (name, Entry { path, .. }) = registry.resolve(query)?;
+
+    let target = match matches.get_one::<PathBuf>("target") {
+        Some(target) => current_dir.join(target),
+        None => current_dir,
+    };
+
+    // What a load of another template recorded does not apply to this one
+    let previous = Lockfile::read(&target)?.filter(|lockfile| lockfile.template == *name);
+
+    let answers = match (matches.get_one::<PathBuf>("answers"), &previous) {
+        (Some(answers_path), _) => load::read_answers(answers_path)?,
+        (None, Some(lockfile)) => Answers::from_recorded(lockfile.answers.clone()),
+        (None, None) => Answers::new(),
+    };
+
+    // Files are compared in the form the load copied them, a followed symlink is a file rather than a link
+    let copy = previous.as_ref().map_or_else(CopyOptions::default, |lockfile| lockfile.copy);
+
+    let context = load::prepare(path, answers, &Prompter::new(matches))?;
+    let (plan, _) = load::build_plan(path, &target, &context, &copy)?;
+
+    let color = io::stdout().is_terminal();
+    let mut differences = 0;
+
+    for step in &plan.steps {
+        if let Action::CreateDirectory = step.action {
+            continue;
+        }
+
+        let rendered = step.action.read_content()
+            .map_err(|source| LoadError::Io { action: "read template file", path: step.action.source(), source })?;
+
+        // A file renamed beside one of the user's own is compared where it was written
+        let key = lockfile::file_key(&step.path);
+        let key = previous.as_ref().and_then(|lockfile| lockfile.renamed.get(&key)).cloned().unwrap_or(key);
+
+        let current_path = target.join(&key);
+        let exists = current_path.exists() || current_path.is_symlink();
+
+        // Files missing from the directory are shown as added in full
+        let current = if exists {
+            plan::read_existing(&current_path, &step.action)
+                .map_err(|source| LoadError::Io { action: "read", path: current_path.clone(), source })?
+        } else {
+            Vec::new()
+        };
+
+        if exists && current == rendered {
+            continue;
+        }
+
+        differences += 1;
+        print!("{}", diff::unified(&key, &current, &rendered, color));
+    }
+
+    if differences == 0 {
+        println!("'{}' matches template {}.", target.display(), name);
+        return Ok(());
+    }
+
+    return Err(CommandError::Unsuccessful(format!("{} file(s) differ from template {}", differences, name)));
+}
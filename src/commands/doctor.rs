@@ -0,0 +1,153 @@
+use crate::commands::error::CommandError;
+use crate::prompt::Prompter;
+use crate::registry::error::RegistryError;
+use crate::registry::location::registry_path;
+use crate::registry::lock::RegistryLock;
+use crate::registry::{Entry, Registry};
+use crate::task::error::Error;
+use crate::task::nodes::collection::NodeCollection;
+use crate::task::pipeline::{parse_enviroment_file, parse_task, ENVIROMENT_FILE_NAME, TASK_FILE_NAME};
+use clap::ArgMatches;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub fn run(matches: &ArgMatches) -> Result<(), CommandError> {
+    let registry = Registry::read()?;
+
+    println!("Checking {} template(s) in {}", registry.templates.len(), registry_path()?.display());
+
+    let mut problems = 0;
+    let mut broken: Vec<&str> = Vec::new();
+
+    for (name, entry) in &registry.templates {
+        let issues = check_template(entry);
+
+        if issues.is_empty() {
+            println!("{}: ok", name);
+            continue;
+        }
+
+        if !entry.path.is_dir() {
+            broken.push(name);
+        }
+
+        problems += issues.len();
+
+        println!("{}:", name);
+
+        for issue in issues {
+            println!("  {}", issue);
+        }
+    }
+
+    for (path, names) in duplicate_paths(&registry) {
+        problems += 1;
+        println!("{}: share the directory '{}'", names.join(", "), path.display());
+    }
+
+    if problems == 0 {
+        println!("No problems found.");
+        return Ok(());
+    }
+
+    println!("Found {} problem(s)", problems);
+
+    if !broken.is_empty() {
+        let selected = if matches.get_flag("fix") {
+            broken
+        } else {
+            let selection = Prompter::new(matches)
+                .multi_select("Select the templates with missing directories to unlink", &broken, &vec![true; broken.len()])?;
+
+            selection.into_iter().map(|index| broken[index]).collect()
+        };
+
+        let pruned = prune(&selected)?;
+        problems -= pruned.len();
+
+        for name in pruned {
+            println!("Template {} is now unlinked.", name);
+        }
+    }
+
+    if problems > 0 {
+        return Err(CommandError::Unsuccessful(format!("{} problem(s) left", problems)));
+    }
+
+    return Ok(());
+}
+
+fn check_template(entry: &Entry) -> Vec<String> {
+    if !entry.path.is_dir() {
+        return vec![format!("directory '{}' does not exist", entry.path.display())];
+    }
+
+    let mut issues = Vec::new();
+
+    check_file(&entry.path.join(TASK_FILE_NAME), &mut issues, |content| match parse_task(content) {
+        NodeCollection::Ok(_) => Vec::new(),
+        NodeCollection::Failed(errors) => errors,
+    });
+
+    check_file(&entry.path.join(ENVIROMENT_FILE_NAME), &mut issues, |content| match parse_enviroment_file(content) {
+        NodeCollection::Ok(_) => Vec::new(),
+        NodeCollection::Failed(errors) => errors,
+    });
+
+    return issues;
+}
+
+fn check_file(path: &Path, issues: &mut Vec<String>, parse: impl FnOnce(&str) -> Vec<Error>) {
+    if !path.exists() {
+        return;
+    }
+
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+
+    match fs::read_to_string(path) {
+        Ok(content) => {
+            for error in parse(&content) {
+                issues.push(format!("{}: {}", file_name, error));
+            }
+        }
+
+        Err(err) => issues.push(format!("{}: could not be read ({})", file_name, err)),
+    }
+}
+
+// Paths are compared after resolving symlinks and relative components, when possible
+fn duplicate_paths(registry: &Registry) -> BTreeMap<PathBuf, Vec<&str>> {
+    let mut paths: BTreeMap<PathBuf, Vec<&str>> = BTreeMap::new();
+
+    for (name, entry) in &registry.templates {
+        let path = fs::canonicalize(&entry.path).unwrap_or_else(|_| entry.path.clone());
+        paths.entry(path).or_default().push(name);
+    }
+
+    paths.retain(|_, names| names.len() > 1);
+
+    return paths;
+}
+
+/// Unlinks the given templates, skipping any whose directory reappeared since the check.
+fn prune<'a>(names: &[&'a str]) -> Result<Vec<&'a str>, RegistryError> {
+    if names.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let lock = RegistryLock::acquire()?;
+    let mut registry = Registry::read_locked(&lock)?;
+    let mut pruned = Vec::new();
+
+    for name in names {
+        if registry.templates.get(*name).is_some_and(|entry| !entry.path.is_dir()) {
+            registry.templates.remove(*name);
+            pruned.push(*name);
+        }
+    }
+
+    registry.write(&lock)?;
+
+    return Ok(pruned);
+}
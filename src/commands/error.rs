@@ -0,0 +1,41 @@
+use crate::load::error::LoadError;
+use crate::prompt::PromptError;
+use crate::registry::error::RegistryError;
+use std::fmt::{Display, Formatter};
+
+pub enum CommandError {
+    Registry(RegistryError),
+    Load(LoadError),
+    Prompt(PromptError),
+    /// The command ran to the end but what it checked is not right, the message sums up what it already printed.
+    Unsuccessful(String),
+}
+
+impl From<RegistryError> for CommandError {
+    fn from(err: RegistryError) -> Self {
+        CommandError::Registry(err)
+    }
+}
+
+impl From<LoadError> for CommandError {
+    fn from(err: LoadError) -> Self {
+        CommandError::Load(err)
+    }
+}
+
+impl From<PromptError> for CommandError {
+    fn from(err: PromptError) -> Self {
+        CommandError::Prompt(err)
+    }
+}
+
+impl Display for CommandError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommandError::Registry(err) => write!(f, "{}", err),
+            CommandError::Load(err) => write!(f, "{}", err),
+            CommandError::Prompt(err) => write!(f, "{}", err),
+            CommandError::Unsuccessful(message) => write!(f, "{}", message),
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use crate::commands::error::CommandError;
+use crate::registry::Registry;
+use crate::task::layers::parsers::enviroment::Statement;
+use crate::task::nodes::collection::NodeCollection;
+use crate::task::nodes::node::Node;
+use crate::task::pipeline::{parse_enviroment_file, ENVIROMENT_FILE_NAME};
+use clap::ArgMatches;
+use std::fs;
+use std::path::Path;
+
+pub fn run(matches: &ArgMatches) -> Result<(), CommandError> {
+    let query = matches
+        .get_one::<String>("name")
+        .expect("Get name argument");
+    let registry = Registry::read()?;
+
+    let (name, entry) = registry.resolve(query)?;
+
+    let metadata = &entry.metadata;
+
+    println!("Name:        {}", name);
+    println!("Path:        {}", entry.path.display());
+    println!("Description: {}", metadata.description.as_deref().unwrap_or("-").replace('\n', "\n             "));
+    println!("Tags:        {}", if metadata.tags.is_empty() { String::from("-") } else { metadata.tags.join(", ") });
+    println!("Author:      {}", metadata.author.as_deref().unwrap_or("-"));
+    println!("Version:     {}", metadata.version.as_deref().unwrap_or("-"));
+    println!("Created:     {}", entry.created.as_deref().unwrap_or("-"));
+    println!("Last used:   {}", entry.last_used.as_deref().unwrap_or("never"));
+
+    print_questions(&entry.path.join(ENVIROMENT_FILE_NAME));
+
+    return Ok(());
+}
+
+fn print_questions(path: &Path) {
+    if !path.exists() {
+        return;
+    }
+
+    println!();
+
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) => {
+            println!("Could not read '{}': {}", path.display(), err);
+            return;
+        }
+    };
+
+    let statements = match parse_enviroment_file(&content) {
+        NodeCollection::Ok(statements) => statements,
+        NodeCollection::Failed(errors) => {
+            println!("Could not parse '{}':", path.display());
+
+            for error in errors {
+                println!("  {}", error);
+            }
+            return;
+        }
+    };
+
+    println!("Questions:");
+    print_statements(&statements, 1);
+}
+
+fn print_statements(statements: &[Node<Statement>], depth: usize) {
+    let indent = "  ".repeat(depth);
+
+    for statement in statements {
+        match &statement.data {
+            Statement::Declaration { identifier, field_type, prompt, validator } => {
+                match validator {
+                    Some(validator) => println!("{}${}: {} > {} :: {}", indent, identifier, field_type, prompt, validator),
+                    None => println!("{}${}: {} > {}", indent, identifier, field_type, prompt),
+                }
+            }
+
+            Statement::Match { identifier, cases } => {
+                println!("{}when ${}", indent, identifier);
+
+                for case in cases.iter() {
+                    let patterns = case.patterns.iter()
+                        .map(|pattern| format!("{}", pattern))
+                        .collect::<Vec<_>>();
+
+                    println!("{}  is {}", indent, patterns.join(" | "));
+                    print_statements(&case.expression, depth + 2);
+                }
+            }
+        }
+    }
+}
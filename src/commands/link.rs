@@ -0,0 +1,44 @@
+use crate::commands::error::CommandError;
+use crate::prompt::Prompter;
+use crate::registry::lock::RegistryLock;
+use crate::registry::metadata::Metadata;
+use crate::registry::name;
+use crate::registry::{Entry, Registry};
+use clap::ArgMatches;
+use std::env;
+
+pub fn run(matches: &ArgMatches) -> Result<(), CommandError> {
+    let current_dir = env::current_dir().expect("Get current directory");
+
+    let name = match matches.get_one::<String>("name") {
+        Some(name) => name.trim().to_string(),
+        None => Prompter::new(matches)
+            .input("Enter a name for the template", "pass the name as an argument", |input| {
+                name::validate(input.trim()).map_err(|err| err.to_string())
+            })?
+            .trim()
+            .to_string(),
+    };
+
+    let overrides = Metadata {
+        description: matches.get_one::<String>("description").cloned(),
+        tags: matches.get_many::<String>("tag").map_or_else(Vec::new, |tags| tags.cloned().collect()),
+        author: matches.get_one::<String>("author").cloned(),
+        version: matches.get_one::<String>("version").cloned(),
+    };
+
+    let metadata = Metadata::read_manifest(&current_dir)?.merge(overrides);
+
+    let lock = RegistryLock::acquire()?;
+    let mut registry = Registry::read_locked(&lock)?;
+
+    registry.check_name(&name)?;
+    registry.check_path(&current_dir, None)?;
+
+    registry.templates.insert(name.to_string(), Entry::new(current_dir, metadata));
+    registry.write(&lock)?;
+
+    println!("Template {} is now linked.", name);
+
+    return Ok(());
+}
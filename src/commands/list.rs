@@ -0,0 +1,243 @@
+use crate::commands::error::CommandError;
+use crate::registry::name;
+use crate::registry::{Entry, Registry};
+use crate::task::pipeline::{ENVIROMENT_FILE_NAME, TASK_FILE_NAME};
+use clap::ArgMatches;
+use glob::{Pattern, PatternError};
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct Listing<'a> {
+    name: &'a str,
+    path: String,
+    description: Option<&'a str>,
+    tags: &'a [String],
+    author: Option<&'a str>,
+    version: Option<&'a str>,
+    created: Option<&'a str>,
+    last_used: Option<&'a str>,
+    exists: bool,
+    has_task: bool,
+    has_env: bool,
+}
+
+impl<'a> Listing<'a> {
+    fn new(name: &'a str, entry: &'a Entry) -> Self {
+        Listing {
+            name,
+            path: entry.path.display().to_string(),
+            description: entry.metadata.summary(),
+            tags: &entry.metadata.tags,
+            author: entry.metadata.author.as_deref(),
+            version: entry.metadata.version.as_deref(),
+            created: entry.created.as_deref(),
+            last_used: entry.last_used.as_deref(),
+            exists: entry.path.is_dir(),
+            has_task: entry.path.join(TASK_FILE_NAME).is_file(),
+            has_env: entry.path.join(ENVIROMENT_FILE_NAME).is_file(),
+        }
+    }
+
+    /// Substitutes `{field}` placeholders and `\t`/`\n` escapes of a user supplied format string.
+    /// The format is scanned once, so placeholders inside substituted values are left as they are.
+    fn format(&self, format: &str) -> String {
+        let mut output = String::new();
+        let mut rest = format;
+
+        while let Some(index) = rest.find(['\\', '{']) {
+            output.push_str(&rest[..index]);
+            rest = &rest[index..];
+
+            if let Some(after) = rest.strip_prefix("\\t") {
+                output.push('\t');
+                rest = after;
+                continue;
+            }
+
+            if let Some(after) = rest.strip_prefix("\\n") {
+                output.push('\n');
+                rest = after;
+                continue;
+            }
+
+            let placeholder = rest.find('}').and_then(|end| Some((self.field(&rest[1..end])?, end)));
+
+            if let (true, Some((value, end))) = (rest.starts_with('{'), placeholder) {
+                output.push_str(&value);
+                rest = &rest[end + 1..];
+                continue;
+            }
+
+            // Neither an escape nor a known placeholder, kept as it is
+            output.push_str(&rest[..1]);
+            rest = &rest[1..];
+        }
+
+        output.push_str(rest);
+
+        return output;
+    }
+
+    fn field(&self, name: &str) -> Option<String> {
+        let value = match name {
+            "name" => self.name.to_string(),
+            "path" => self.path.clone(),
+            "description" => self.description.unwrap_or("").to_string(),
+            "tags" => self.tags.join(","),
+            "author" => self.author.unwrap_or("").to_string(),
+            "version" => self.version.unwrap_or("").to_string(),
+            "created" => self.created.unwrap_or("").to_string(),
+            "last_used" => self.last_used.unwrap_or("").to_string(),
+            "exists" => self.exists.to_string(),
+            "has_task" => self.has_task.to_string(),
+            "has_env" => self.has_env.to_string(),
+            _ => return None,
+        };
+
+        return Some(value);
+    }
+}
+
+enum NameFilter {
+    Namespace(String),
+    Glob(Pattern),
+}
+
+impl NameFilter {
+    /// Plain names select a namespace group, anything with glob characters is matched as a glob.
+    fn new(pattern: &str) -> Result<Self, PatternError> {
+        if pattern.contains(['*', '?', '[']) {
+            return Ok(NameFilter::Glob(Pattern::new(pattern)?));
+        }
+
+        return Ok(NameFilter::Namespace(pattern.to_string()));
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        match self {
+            NameFilter::Namespace(namespace) => name == namespace || name::in_namespace(name, namespace),
+            NameFilter::Glob(pattern) => pattern.matches(name),
+        }
+    }
+}
+
+pub fn run(matches: &ArgMatches) -> Result<(), CommandError> {
+    let registry = Registry::read()?;
+
+    let filter = match matches.get_one::<String>("pattern") {
+        Some(pattern) => match NameFilter::new(pattern) {
+            Ok(filter) => Some(filter),
+            Err(err) => {
+                println!("Invalid name pattern: {}", err);
+                return Ok(());
+            }
+        },
+        None => None,
+    };
+
+    let tags = matches.get_many::<String>("tag")
+        .map_or_else(Vec::new, |tags| tags.collect::<Vec<_>>());
+
+    // The registry is ordered by name, so listings are sorted as well
+    let listings = registry.templates.iter()
+        .filter(|(name, _)| filter.as_ref().is_none_or(|filter| filter.matches(name)))
+        .filter(|(_, entry)| tags.iter().all(|tag| entry.metadata.tags.contains(tag)))
+        .map(|(name, entry)| Listing::new(name, entry))
+        .collect::<Vec<_>>();
+
+    if matches.get_flag("json") {
+        println!("{}", serde_json::to_string_pretty(&listings).expect("Serialize template list"));
+        return Ok(());
+    }
+
+    if let Some(format) = matches.get_one::<String>("format") {
+        for listing in &listings {
+            println!("{}", listing.format(format));
+        }
+        return Ok(());
+    }
+
+    if listings.is_empty() {
+        if registry.templates.is_empty() {
+            println!("No templates linked.");
+        } else {
+            println!("No templates match the given filters.");
+        }
+
+        return Ok(());
+    }
+
+    if matches.get_flag("long") {
+        print_long(&listings);
+        return Ok(());
+    }
+
+    println!("Linked templates:");
+
+    for listing in &listings {
+        match listing.description {
+            Some(summary) => println!("{} -> {} - {}", listing.name, listing.path, summary),
+            None => println!("{} -> {}", listing.name, listing.path),
+        }
+    }
+
+    return Ok(());
+}
+
+fn print_long(listings: &[Listing]) {
+    let width = listings.iter()
+        .map(|listing| listing.name.len())
+        .max()
+        .unwrap_or(0)
+        .max("NAME".len());
+
+    println!("{:<width$}  {:<7}  {:<4}  {:<4}  PATH", "NAME", "STATUS", "TASK", "ENV");
+
+    for listing in listings {
+        println!("{:<width$}  {:<7}  {:<4}  {:<4}  {}",
+                 listing.name,
+                 if listing.exists { "ok" } else { "missing" },
+                 if listing.has_task { "yes" } else { "no" },
+                 if listing.has_env { "yes" } else { "no" },
+                 listing.path
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Listing;
+
+    fn listing<'a>(description: &'a str, tags: &'a [String]) -> Listing<'a> {
+        Listing {
+            name: "web/app",
+            path: String::from("/templates/{name}"),
+            description: Some(description),
+            tags,
+            author: None,
+            version: None,
+            created: None,
+            last_used: None,
+            exists: true,
+            has_task: false,
+            has_env: false,
+        }
+    }
+
+    #[test]
+    fn substitutes_fields_and_escapes() {
+        let tags = [String::from("rust"), String::from("cli")];
+
+        assert_eq!(listing("An app", &tags).format("{name}\\t{tags}\\n{exists}"), "web/app\trust,cli\ntrue");
+    }
+
+    #[test]
+    fn leaves_placeholders_inside_values() {
+        assert_eq!(listing("uses {tags}", &[]).format("{path} {description}"), "/templates/{name} uses {tags}");
+    }
+
+    #[test]
+    fn keeps_unknown_placeholders_and_braces() {
+        assert_eq!(listing("", &[]).format("{unknown} {name"), "{unknown} {name");
+    }
+}
@@ -0,0 +1,109 @@
+use crate::commands::error::CommandError;
+use crate::load;
+use crate::load::conflict::ConflictPolicy;
+use crate::load::copy::{CopyOptions, SymlinkPolicy};
+use crate::load::preview;
+use crate::load::LoadOptions;
+use crate::prompt::Prompter;
+use crate::registry::error::RegistryError;
+use crate::registry::lock::RegistryLock;
+use crate::registry::{Entry, Registry};
+use crate::task::answers::Answers;
+use crate::task::context::Context;
+use crate::timestamp;
+use clap::ArgMatches;
+use std::env;
+use std::path::{Path, PathBuf};
+
+pub fn run(matches: &ArgMatches) -> Result<(), CommandError> {
+    let current_dir = env::current_dir().expect("Get current directory");
+
+    let query = matches
+        .get_one::<String>("name")
+        .expect("Get name argument");
+    let registry = Registry::read()?;
+
+    let (name, Entry { path, .. }) = registry.resolve(query)?;
+
+    let target = match matches.get_one::<PathBuf>("target") {
+        Some(target) => current_dir.join(target),
+        None => current_dir,
+    };
+
+    load::check_target(path, &target)?;
+
+    let options = LoadOptions {
+        conflict: matches.get_one::<String>("on-conflict")
+            .and_then(|policy| ConflictPolicy::parse(policy))
+            .unwrap_or(ConflictPolicy::Prompt),
+        copy: CopyOptions {
+            symlinks: matches.get_one::<String>("symlinks")
+                .and_then(|policy| SymlinkPolicy::parse(policy))
+                .unwrap_or(SymlinkPolicy::Keep),
+            preserve_mtime: matches.get_flag("preserve-mtime"),
+        },
+    };
+
+    let given = match matches.get_one::<PathBuf>("answers") {
+        Some(answers_path) => load::read_answers(answers_path)?,
+        None => Answers::new(),
+    };
+
+    let prompter = Prompter::new(matches);
+
+    if matches.get_flag("dry-run") {
+        let context = load::prepare(path, given, &prompter)?;
+
+        return print_plan(name, path, &target, &context, &options);
+    }
+
+    let prompt = if load::is_empty(&target) || matches.get_flag("force") {
+        format!("Do you want to load the template '{}' into '{}'?", name, target.display())
+    } else {
+        format!("The directory '{}' is not empty, do you want to load the template '{}' into it anyway?", target.display(), name)
+    };
+
+    if !prompter.confirm(&prompt)? {
+        return Ok(());
+    }
+
+    let context = load::prepare(path, given, &prompter)?;
+
+    println!("Loading template from {}", path.display());
+
+    load::load(name, path, &target, &context, &options, &prompter)?;
+
+    println!("Finished loading template");
+
+    return Ok(mark_used(name)?);
+}
+
+fn print_plan(name: &str, origin: &Path, target: &Path, context: &Context, options: &LoadOptions) -> Result<(), CommandError> {
+    let (changes, rewrites) = load::dry_run(origin, target, context, options)?;
+
+    println!("Loading '{}' into '{}' would change:", name, target.display());
+
+    for change in &changes {
+        println!("  {}", change);
+    }
+
+    println!("{}", preview::summary(&changes));
+
+    for rewrite in &rewrites {
+        println!("  {}", rewrite);
+    }
+
+    return Ok(());
+}
+
+fn mark_used(name: &str) -> Result<(), RegistryError> {
+    let lock = RegistryLock::acquire()?;
+    let mut registry = Registry::read_locked(&lock)?;
+
+    if let Some(entry) = registry.templates.get_mut(name) {
+        entry.last_used = Some(timestamp::now());
+        registry.write(&lock)?;
+    }
+
+    return Ok(());
+}
@@ -0,0 +1,13 @@
+pub mod error;
+pub mod diff;
+pub mod doctor;
+pub mod info;
+pub mod link;
+pub mod list;
+pub mod load;
+pub mod registry;
+pub mod relink;
+pub mod rename;
+pub mod undo;
+pub mod unlink;
+pub mod update;
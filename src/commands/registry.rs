@@ -0,0 +1,9 @@
+use crate::commands::error::CommandError;
+use crate::registry::location::registry_path;
+use clap::ArgMatches;
+
+pub fn run(_matches: &ArgMatches) -> Result<(), CommandError> {
+    println!("{}", registry_path()?.display());
+
+    return Ok(());
+}
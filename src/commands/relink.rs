@@ -0,0 +1,35 @@
+use crate::commands::error::CommandError;
+use crate::registry::error::RegistryError;
+use crate::registry::lock::RegistryLock;
+use crate::registry::Registry;
+use clap::ArgMatches;
+use std::fs;
+use std::path::PathBuf;
+
+pub fn run(matches: &ArgMatches) -> Result<(), CommandError> {
+    let name = matches
+        .get_one::<String>("name")
+        .expect("Get name argument");
+    let path = matches
+        .get_one::<PathBuf>("path")
+        .expect("Get path argument");
+
+    let path = fs::canonicalize(path)
+        .map_err(|source| RegistryError::Io { action: "resolve template directory", path: path.clone(), source })?;
+
+    let lock = RegistryLock::acquire()?;
+    let mut registry = Registry::read_locked(&lock)?;
+
+    registry.get(name)?;
+    registry.check_path(&path, Some(name))?;
+
+    if let Some(entry) = registry.templates.get_mut(name) {
+        entry.path = path.clone();
+    }
+
+    registry.write(&lock)?;
+
+    println!("Template {} now points to {}.", name, path.display());
+
+    return Ok(());
+}
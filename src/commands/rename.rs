@@ -0,0 +1,29 @@
+use crate::commands::error::CommandError;
+use crate::registry::lock::RegistryLock;
+use crate::registry::Registry;
+use clap::ArgMatches;
+
+pub fn run(matches: &ArgMatches) -> Result<(), CommandError> {
+    let name = matches
+        .get_one::<String>("name")
+        .expect("Get name argument");
+    let new_name = matches
+        .get_one::<String>("new-name")
+        .expect("Get new name argument")
+        .trim();
+
+    let lock = RegistryLock::acquire()?;
+    let mut registry = Registry::read_locked(&lock)?;
+
+    registry.get(name)?;
+    registry.check_name(new_name)?;
+
+    let entry = registry.templates.remove(name).expect("Remove renamed template");
+
+    registry.templates.insert(new_name.to_string(), entry);
+    registry.write(&lock)?;
+
+    println!("Template {} is now named {}.", name, new_name);
+
+    return Ok(());
+}
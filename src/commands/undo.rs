@@ -0,0 +1,50 @@
+use crate::commands::error::CommandError;
+use crate::load::error::LoadError;
+use crate::load::journal::{self, Record};
+use crate::load::undo;
+use crate::prompt::Prompter;
+use clap::ArgMatches;
+use std::env;
+use std::path::PathBuf;
+
+pub fn run(matches: &ArgMatches) -> Result<(), CommandError> {
+    let current_dir = env::current_dir().expect("Get current directory");
+
+    let target = match matches.get_one::<PathBuf>("target") {
+        Some(target) => current_dir.join(target),
+        None => current_dir,
+    };
+
+    let Some(recorded) = journal::history(&target)?.pop() else {
+        return Err(LoadError::NothingToUndo(target).into());
+    };
+
+    let modified = undo::modified(&target, &recorded);
+
+    if !modified.is_empty() && !matches.get_flag("force") {
+        return Err(LoadError::Modified(modified).into());
+    }
+
+    let created = recorded.records.iter().filter(|record| matches!(record, Record::File { .. })).count();
+    let overwritten = recorded.records.iter().filter(|record| matches!(record, Record::Overwrite { .. } | Record::Remove { .. })).count();
+
+    let prompt = format!("Undo loading '{}' into '{}' at {}? This removes {} file(s) and restores {}",
+                         recorded.template,
+                         target.display(),
+                         recorded.started,
+                         created,
+                         overwritten
+    );
+
+    if !Prompter::new(matches).confirm(&prompt)? {
+        return Ok(());
+    }
+
+    for line in undo::undo(&target, recorded) {
+        println!("  {}", line);
+    }
+
+    println!("Finished undoing the load");
+
+    return Ok(());
+}
@@ -0,0 +1,36 @@
+use crate::commands::error::CommandError;
+use crate::registry::lock::RegistryLock;
+use crate::registry::Registry;
+use clap::ArgMatches;
+use std::env;
+
+pub fn run(matches: &ArgMatches) -> Result<(), CommandError> {
+    let lock = RegistryLock::acquire()?;
+    let mut registry = Registry::read_locked(&lock)?;
+
+    if let Some(name) = matches.get_one::<String>("name") {
+        registry.get(name)?;
+        registry.templates.remove(name);
+        registry.write(&lock)?;
+
+        println!("Template {} is now unlinked.", name);
+
+        return Ok(());
+    }
+
+    let current_dir = env::current_dir().expect("Get current directory");
+    let count = registry.templates.len();
+
+    registry.templates.retain(|_, entry| entry.path != current_dir);
+
+    if registry.templates.len() == count {
+        println!("The directory '{}' is not linked as a template.", current_dir.display());
+        return Ok(());
+    }
+
+    registry.write(&lock)?;
+
+    println!("Template {} is now unlinked.", current_dir.display());
+
+    return Ok(());
+}
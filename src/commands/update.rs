@@ -0,0 +1,69 @@
+use crate::commands::error::CommandError;
+use crate::load;
+use crate::load::error::LoadError;
+use crate::load::lockfile::{self, Lockfile};
+use crate::load::update;
+use crate::prompt::Prompter;
+use crate::registry::Registry;
+use crate::task::answers::Answers;
+use clap::ArgMatches;
+use std::env;
+use std::path::PathBuf;
+
+pub fn run(matches: &ArgMatches) -> Result<(), CommandError> {
+    let current_dir = env::current_dir().expect("Get current directory");
+
+    let target = match matches.get_one::<PathBuf>("target") {
+        Some(target) => current_dir.join(target),
+        None => current_dir,
+    };
+
+    let Some(previous) = Lockfile::read(&target)? else {
+        return Err(LoadError::NotGenerated(target).into());
+    };
+
+    let registry = Registry::read()?;
+    let name = previous.template.as_str();
+    let origin = &registry.get(name)?.path;
+
+    load::check_target(origin, &target)?;
+
+    // Only questions added to the template since the last load, or secret ones, are asked
+    let prompter = Prompter::new(matches);
+    let context = load::prepare(origin, Answers::from_recorded(previous.answers.clone()), &prompter)?;
+    // Files are produced the way the last load copied them, otherwise followed symlinks would turn back into links
+    let copy = previous.copy;
+
+    let (plan, _) = load::build_plan(origin, &target, &context, &copy)?;
+
+    let template_hash = lockfile::template_hash(origin, &plan)?;
+
+    if template_hash == previous.template_hash {
+        println!("'{}' is already up to date with template {}.", target.display(), name);
+        return Ok(());
+    }
+
+    let prompt = format!("Do you want to update '{}' to the current version of template '{}'?", target.display(), name);
+
+    if !prompter.confirm(&prompt)? {
+        return Ok(());
+    }
+
+    let outcomes = load::transaction(name, &target, |journal| {
+        update::update(&plan, template_hash, &target, context.answers(), &previous, &copy, journal)
+    })?;
+
+    for (path, outcome) in &outcomes {
+        println!("  {}: {}", path, outcome);
+    }
+
+    let conflicts = outcomes.iter().filter(|(_, outcome)| outcome.is_conflict()).count();
+
+    if conflicts > 0 {
+        println!("Updated with {} conflict(s), `plat undo` reverts the update.", conflicts);
+    } else {
+        println!("Finished updating to the current version of template {}", name);
+    }
+
+    return Ok(());
+}
@@ -0,0 +1,130 @@
+use crate::load::diff;
+use crate::load::error::LoadError;
+use crate::prompt::Prompter;
+use std::fmt::{Display, Formatter};
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+
+pub const CONFLICT_POLICIES: &str = "skip, overwrite, prompt, rename or fail";
+
+/// What to do when a file the template produces already exists in the target.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConflictPolicy {
+    Skip,
+    Overwrite,
+    Prompt,
+    Rename,
+    Fail,
+}
+
+impl ConflictPolicy {
+    pub fn parse(name: &str) -> Option<ConflictPolicy> {
+        match name {
+            "skip" => Some(ConflictPolicy::Skip),
+            "overwrite" => Some(ConflictPolicy::Overwrite),
+            "prompt" => Some(ConflictPolicy::Prompt),
+            "rename" => Some(ConflictPolicy::Rename),
+            "fail" => Some(ConflictPolicy::Fail),
+            _ => None,
+        }
+    }
+}
+
+impl Display for ConflictPolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConflictPolicy::Skip => write!(f, "skip"),
+            ConflictPolicy::Overwrite => write!(f, "overwrite"),
+            ConflictPolicy::Prompt => write!(f, "prompt"),
+            ConflictPolicy::Rename => write!(f, "rename"),
+            ConflictPolicy::Fail => write!(f, "fail"),
+        }
+    }
+}
+
+pub enum Resolution {
+    Overwrite,
+    Skip,
+    Rename(PathBuf),
+}
+
+const CHOICES: &[&str] = &[
+    "Overwrite",
+    "Skip",
+    "Keep both (write the new file next to it)",
+    "Show diff",
+    "Overwrite all",
+    "Skip all",
+];
+
+/// Decides what happens to each conflicting file, remembering "all" answers given at a prompt.
+pub struct ConflictResolver<'a> {
+    default: ConflictPolicy,
+    prompter: &'a Prompter,
+    remembered: Option<ConflictPolicy>,
+}
+
+impl<'a> ConflictResolver<'a> {
+    pub fn new(default: ConflictPolicy, prompter: &'a Prompter) -> Self {
+        ConflictResolver { default, prompter, remembered: None }
+    }
+
+    /// Resolves a conflict at `path`, `relative` is the path shown to the user.
+    /// The policy of the instruction producing the file takes precedence over the default one.
+    pub fn resolve(&mut self, path: &Path, relative: &Path, existing: &[u8], incoming: &[u8], policy: Option<ConflictPolicy>) -> Result<Resolution, LoadError> {
+        let policy = match (policy, self.remembered) {
+            (Some(policy), _) => policy,
+            (None, Some(remembered)) => remembered,
+            (None, None) => self.default,
+        };
+
+        return match policy {
+            ConflictPolicy::Skip => Ok(Resolution::Skip),
+            ConflictPolicy::Overwrite => Ok(Resolution::Overwrite),
+            ConflictPolicy::Rename => Ok(Resolution::Rename(free_path(path))),
+            ConflictPolicy::Fail => Err(LoadError::Conflict(path.to_path_buf())),
+            ConflictPolicy::Prompt => self.prompt(path, relative, existing, incoming),
+        };
+    }
+
+    fn prompt(&mut self, path: &Path, relative: &Path, existing: &[u8], incoming: &[u8]) -> Result<Resolution, LoadError> {
+        let prompt = format!("'{}' already exists", relative.display());
+
+        loop {
+            let choice = self.prompter
+                .select(&prompt, CHOICES, "pass --on-conflict to choose how existing files are handled")
+                .map_err(LoadError::Prompt)?;
+
+            match choice {
+                0 => return Ok(Resolution::Overwrite),
+                1 => return Ok(Resolution::Skip),
+                2 => return Ok(Resolution::Rename(free_path(path))),
+                3 => print!("{}", diff::unified(&relative.to_string_lossy(), existing, incoming, io::stdout().is_terminal())),
+
+                4 => {
+                    self.remembered = Some(ConflictPolicy::Overwrite);
+                    return Ok(Resolution::Overwrite);
+                }
+
+                _ => {
+                    self.remembered = Some(ConflictPolicy::Skip);
+                    return Ok(Resolution::Skip);
+                }
+            }
+        }
+    }
+}
+
+/// Finds a name next to `path` that is not taken yet, like `config.toml.plat-new`.
+pub fn free_path(path: &Path) -> PathBuf {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let mut candidate = path.with_file_name(format!("{}.plat-new", name));
+    let mut index = 2;
+
+    while candidate.exists() {
+        candidate = path.with_file_name(format!("{}.plat-new-{}", name, index));
+        index += 1;
+    }
+
+    return candidate;
+}
@@ -0,0 +1,119 @@
+use filetime::FileTime;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// What to do with symlinks found in a template.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SymlinkPolicy {
+    Keep,
+    Follow,
+    Reject,
+}
+
+impl SymlinkPolicy {
+    pub fn parse(name: &str) -> Option<SymlinkPolicy> {
+        match name {
+            "keep" => Some(SymlinkPolicy::Keep),
+            "follow" => Some(SymlinkPolicy::Follow),
+            "reject" => Some(SymlinkPolicy::Reject),
+            _ => None,
+        }
+    }
+}
+
+impl Display for SymlinkPolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SymlinkPolicy::Keep => write!(f, "keep"),
+            SymlinkPolicy::Follow => write!(f, "follow"),
+            SymlinkPolicy::Reject => write!(f, "reject"),
+        }
+    }
+}
+
+/// How files are copied out of a template. Permissions are always preserved.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CopyOptions {
+    pub symlinks: SymlinkPolicy,
+    pub preserve_mtime: bool,
+}
+
+impl Default for CopyOptions {
+    fn default() -> Self {
+        CopyOptions { symlinks: SymlinkPolicy::Keep, preserve_mtime: false }
+    }
+}
+
+/// Copies the file at `source` to `destination` with its permission bits, and its modification time
+/// when `options` asks for it. A symlink at `destination` is replaced instead of written through.
+pub fn copy_file(source: &Path, destination: &Path, options: &CopyOptions) -> io::Result<u64> {
+    remove_symlink(destination)?;
+
+    let size = fs::copy(source, destination)?;
+    let metadata = fs::metadata(source)?;
+
+    // fs::copy already carries the permissions over on most platforms, setting them again covers the rest
+    fs::set_permissions(destination, metadata.permissions())?;
+
+    if options.preserve_mtime {
+        filetime::set_file_mtime(destination, FileTime::from_last_modification_time(&metadata))?;
+    }
+
+    return Ok(size);
+}
+
+/// Writes generated `content` to `destination` with the permissions of the `source` it was derived from.
+/// A symlink at `destination` is replaced instead of written through.
+pub fn write_file(source: &Path, content: &[u8], destination: &Path) -> io::Result<u64> {
+    let permissions = fs::metadata(source)?.permissions();
+
+    remove_symlink(destination)?;
+    fs::write(destination, content)?;
+    fs::set_permissions(destination, permissions)?;
+
+    return Ok(content.len() as u64);
+}
+
+/// Gives `destination` the permissions of `source`, for files whose content was generated rather than copied.
+pub fn copy_permissions(source: &Path, destination: &Path) -> io::Result<()> {
+    return fs::set_permissions(destination, fs::metadata(source)?.permissions());
+}
+
+/// Creates a symlink at `destination` pointing to `link`, replacing any file or symlink already there.
+pub fn create_symlink(link: &Path, destination: &Path) -> io::Result<()> {
+    if fs::symlink_metadata(destination).is_ok_and(|metadata| !metadata.is_dir()) {
+        fs::remove_file(destination)?;
+    }
+
+    #[cfg(unix)]
+    return std::os::unix::fs::symlink(link, destination);
+
+    #[cfg(windows)]
+    return match destination.parent().map(|parent| parent.join(link)).is_some_and(|resolved| resolved.is_dir()) {
+        true => std::os::windows::fs::symlink_dir(link, destination),
+        false => std::os::windows::fs::symlink_file(link, destination),
+    };
+}
+
+/// Copies `source` to `destination` as it is, recreating symlinks instead of copying what they point to.
+/// Used for backups, so restoring one gives back exactly what was replaced.
+pub fn duplicate(source: &Path, destination: &Path) -> io::Result<()> {
+    if fs::symlink_metadata(source)?.file_type().is_symlink() {
+        return create_symlink(&fs::read_link(source)?, destination);
+    }
+
+    return copy_file(source, destination, &CopyOptions { symlinks: SymlinkPolicy::Keep, preserve_mtime: true }).map(|_| ());
+}
+
+fn remove_symlink(path: &Path) -> io::Result<()> {
+    if fs::symlink_metadata(path).is_ok_and(|metadata| metadata.file_type().is_symlink()) {
+        fs::remove_file(path)?;
+    }
+
+    return Ok(());
+}
@@ -0,0 +1,22 @@
+use diffy::{DiffOptions, PatchFormatter};
+use std::str;
+
+/// Renders a unified diff between two versions of the file at `path`.
+pub fn unified(path: &str, original: &[u8], modified: &[u8], color: bool) -> String {
+    let (Ok(original), Ok(modified)) = (str::from_utf8(original), str::from_utf8(modified)) else {
+        return format!("Binary files a/{} and b/{} differ\n", path, path);
+    };
+
+    let patch = DiffOptions::new()
+        .set_original_filename(format!("a/{}", path))
+        .set_modified_filename(format!("b/{}", path))
+        .create_patch(original, modified);
+
+    let formatter = if color {
+        PatchFormatter::new().with_color()
+    } else {
+        PatchFormatter::new()
+    };
+
+    return formatter.fmt_patch(&patch).to_string();
+}
@@ -0,0 +1,148 @@
+use crate::prompt::PromptError;
+use crate::task::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::PathBuf;
+
+pub enum LoadError {
+    IntoSource {
+        origin: PathBuf,
+        target: PathBuf,
+    },
+    Io {
+        action: &'static str,
+        path: PathBuf,
+        source: io::Error,
+    },
+    Ignore(String),
+    Conflict(PathBuf),
+    Interrupted,
+    Symlink(PathBuf),
+    SymlinkOutside {
+        path: PathBuf,
+        resolved: PathBuf,
+    },
+    OutsideTarget {
+        path: PathBuf,
+        resolved: PathBuf,
+    },
+    Journal {
+        path: PathBuf,
+        message: String,
+    },
+    Lockfile {
+        path: PathBuf,
+        message: String,
+    },
+    Answers {
+        path: PathBuf,
+        message: String,
+    },
+    NothingToUndo(PathBuf),
+    NotGenerated(PathBuf),
+    Modified(Vec<PathBuf>),
+    Prompt(PromptError),
+    Template(Vec<(&'static str, Error)>),
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::IntoSource { origin, target } => {
+                write!(f, "Can not load the template at '{}' into '{}', the target is inside the template itself",
+                       origin.display(),
+                       target.display()
+                )
+            }
+
+            LoadError::Io { action, path, source } => {
+                write!(f, "Failed to {} '{}': {}",
+                       action,
+                       path.display(),
+                       source
+                )
+            }
+
+            LoadError::Ignore(message) => {
+                write!(f, "Invalid ignore rules: {}", message)
+            }
+
+            LoadError::Conflict(path) => {
+                write!(f, "'{}' already exists, pass --on-conflict to choose how existing files are handled", path.display())
+            }
+
+            LoadError::Symlink(path) => {
+                write!(f, "The template contains a symlink at '{}', pass --symlinks keep or follow to load it", path.display())
+            }
+
+            LoadError::SymlinkOutside { path, resolved } => {
+                write!(f, "The symlink at '{}' points to '{}', which is outside of the template",
+                       path.display(),
+                       resolved.display()
+                )
+            }
+
+            LoadError::OutsideTarget { path, resolved } => {
+                write!(f, "Can not write '{}', it resolves to '{}', which is outside of the load target",
+                       path.display(),
+                       resolved.display()
+                )
+            }
+
+            LoadError::Interrupted => {
+                write!(f, "The load was interrupted")
+            }
+
+            LoadError::Journal { path, message } => {
+                write!(f, "The journal at '{}' is malformed: {}",
+                       path.display(),
+                       message
+                )
+            }
+
+            LoadError::Lockfile { path, message } => {
+                write!(f, "The lockfile at '{}' is malformed: {}",
+                       path.display(),
+                       message
+                )
+            }
+
+            LoadError::Answers { path, message } => {
+                write!(f, "The answers file '{}' is malformed: {}",
+                       path.display(),
+                       message
+                )
+            }
+
+            LoadError::NothingToUndo(target) => {
+                write!(f, "There is no recorded load to undo in '{}'", target.display())
+            }
+
+            LoadError::NotGenerated(target) => {
+                write!(f, "No template was loaded into '{}', there is no lockfile to update from", target.display())
+            }
+
+            LoadError::Modified(paths) => {
+                let paths = paths.iter()
+                    .map(|path| format!("  {}", path.display()))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+
+                write!(f, "These files were changed since they were loaded, pass --force to undo anyway:\n{}", paths)
+            }
+
+            LoadError::Prompt(err) => {
+                write!(f, "{}", err)
+            }
+
+            LoadError::Template(errors) => {
+                let errors = errors.iter()
+                    .map(|(file_name, error)| format!("  {}: {}", file_name, error))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+
+                write!(f, "The template can not be loaded:\n{}", errors)
+            }
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+/// The hex encoded SHA-256 digest of `content`.
+pub fn digest(content: &[u8]) -> String {
+    return encode(&Sha256::digest(content));
+}
+
+/// The hex encoded SHA-256 digest of the file at `path`, read in chunks.
+pub fn digest_file(path: &Path) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    let mut file = File::open(path)?;
+
+    io::copy(&mut file, &mut hasher)?;
+
+    return Ok(encode(&hasher.finalize()));
+}
+
+fn encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
@@ -0,0 +1,63 @@
+use crate::load::error::LoadError;
+use crate::registry::metadata::MANIFEST_FILE_NAME;
+use crate::task::pipeline::{ENVIROMENT_FILE_NAME, TASK_FILE_NAME};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::path::Path;
+
+pub const IGNORE_FILE_NAME: &str = ".platignore";
+
+/// Patterns that are never part of a template's output unless `.platignore` re-includes them with `!`.
+const DEFAULT_PATTERNS: &[&str] = &[
+    ".git/",
+    ".hg/",
+    ".svn/",
+    ".idea/",
+    ".vscode/",
+    ".DS_Store",
+    "Thumbs.db",
+    "target/",
+    "node_modules/",
+    "/.plat/",
+    "/.plat-lock",
+    TASK_FILE_NAME,
+    ENVIROMENT_FILE_NAME,
+    IGNORE_FILE_NAME,
+    MANIFEST_FILE_NAME,
+];
+
+/// The ignore rules of a template, built-in defaults followed by the template's `.platignore`,
+/// with the same semantics as a `.gitignore` at the template root.
+pub struct IgnoreRules {
+    matcher: Gitignore,
+}
+
+impl IgnoreRules {
+    pub fn read(origin: &Path) -> Result<IgnoreRules, LoadError> {
+        let mut builder = GitignoreBuilder::new(origin);
+
+        for pattern in DEFAULT_PATTERNS {
+            builder.add_line(None, pattern)
+                .map_err(|err| LoadError::Ignore(err.to_string()))?;
+        }
+
+        let path = origin.join(IGNORE_FILE_NAME);
+
+        if path.exists() {
+            if let Some(err) = builder.add(&path) {
+                return Err(LoadError::Ignore(err.to_string()));
+            }
+        }
+
+        let matcher = builder.build()
+            .map_err(|err| LoadError::Ignore(err.to_string()))?;
+
+        return Ok(IgnoreRules { matcher });
+    }
+
+    /// Whether a path relative to the template root, or any of its parent directories, is ignored.
+    pub fn is_ignored(&self, relative: &Path, is_dir: bool) -> bool {
+        self.matcher
+            .matched_path_or_any_parents(relative, is_dir)
+            .is_ignore()
+    }
+}
@@ -0,0 +1,282 @@
+use crate::load::copy;
+use crate::load::error::LoadError;
+use crate::load::hash;
+use crate::timestamp;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// The directory inside a load target where plat keeps its own state.
+pub const STATE_DIRECTORY_NAME: &str = ".plat";
+pub const LOADS_DIRECTORY_NAME: &str = "loads";
+pub const JOURNAL_FILE_NAME: &str = "journal.toml";
+pub const BACKUPS_DIRECTORY_NAME: &str = "backups";
+
+/// A change made to the target, paths are relative to the target directory.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum Record {
+    Directory { path: PathBuf },
+    File { path: PathBuf, hash: String },
+    Overwrite { path: PathBuf, hash: String },
+    Remove { path: PathBuf },
+}
+
+/// A journal read back from disk, `directory` is where it and its backups are stored.
+#[derive(Deserialize)]
+pub struct Recorded {
+    #[serde(skip)]
+    pub directory: PathBuf,
+    pub template: String,
+    pub started: String,
+    #[serde(default)]
+    pub records: Vec<Record>,
+}
+
+#[derive(Serialize)]
+struct Header<'a> {
+    template: &'a str,
+    started: &'a str,
+}
+
+// A single record in the shape of a toml array of tables, so it can be appended to the journal file
+#[derive(Serialize)]
+struct Appended<'a> {
+    records: [&'a Record; 1],
+}
+
+/// Records every change a load makes to the target as it happens, so the target can be put back the way it was.
+/// The journal is written to `.plat/loads/<started>/journal.toml` and overwritten files are copied to `backups` next to it.
+pub struct Journal {
+    target: PathBuf,
+    directory: PathBuf,
+    file: File,
+    records: Vec<Record>,
+}
+
+impl Journal {
+    pub fn begin(target: &Path, template: &str) -> Result<Journal, LoadError> {
+        let started = timestamp::now();
+        let loads = target.join(STATE_DIRECTORY_NAME).join(LOADS_DIRECTORY_NAME);
+
+        // Timestamps only have a precision of seconds, loads started within the same second get a suffix
+        let name = started.replace(':', "-");
+        let mut directory = loads.join(&name);
+        let mut index = 2;
+
+        while directory.exists() {
+            directory = loads.join(format!("{}-{}", name, index));
+            index += 1;
+        }
+
+        fs::create_dir_all(&directory)
+            .map_err(|source| LoadError::Io { action: "create journal directory", path: directory.clone(), source })?;
+
+        let path = directory.join(JOURNAL_FILE_NAME);
+        let header = toml::to_string(&Header { template, started: &started }).expect("Serialize journal header");
+
+        let mut file = OpenOptions::new().create_new(true).append(true).open(&path)
+            .map_err(|source| LoadError::Io { action: "create journal", path: path.clone(), source })?;
+
+        file.write_all(header.as_bytes())
+            .map_err(|source| LoadError::Io { action: "write journal", path: path.clone(), source })?;
+
+        return Ok(Journal { target: target.to_path_buf(), directory, file, records: Vec::new() });
+    }
+
+    /// Creates `path` and any missing parent directories, recording each one that did not exist.
+    pub fn create_directories(&mut self, path: &Path) -> Result<(), LoadError> {
+        let mut missing = Vec::new();
+        let mut current = path;
+
+        while !current.is_dir() && current.starts_with(&self.target) {
+            missing.push(current.to_path_buf());
+
+            let Some(parent) = current.parent() else {
+                break;
+            };
+
+            current = parent;
+        }
+
+        for directory in missing.into_iter().rev() {
+            fs::create_dir(&directory)
+                .map_err(|source| LoadError::Io { action: "create directory", path: directory.clone(), source })?;
+
+            self.record(Record::Directory { path: self.relative(&directory) })?;
+        }
+
+        return Ok(());
+    }
+
+    /// Records a file that is about to be created at `path`, `hash` is the digest of its new content.
+    pub fn creating(&mut self, path: &Path, hash: String) -> Result<(), LoadError> {
+        return self.record(Record::File { path: self.relative(path), hash });
+    }
+
+    /// Backs up the file at `path` before it is overwritten, `hash` is the digest of its new content.
+    pub fn overwriting(&mut self, path: &Path, hash: String) -> Result<(), LoadError> {
+        self.back_up(path)?;
+
+        return self.record(Record::Overwrite { path: self.relative(path), hash });
+    }
+
+    fn back_up(&self, path: &Path) -> Result<(), LoadError> {
+        let backup = self.directory.join(BACKUPS_DIRECTORY_NAME).join(self.relative(path));
+
+        if let Some(parent) = backup.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|source| LoadError::Io { action: "create backup directory", path: parent.to_path_buf(), source })?;
+        }
+
+        copy::duplicate(path, &backup)
+            .map_err(|source| LoadError::Io { action: "back up", path: path.to_path_buf(), source })?;
+
+        return Ok(());
+    }
+
+    /// Writes `content` to `path`, creating missing directories and backing up any file that is replaced.
+    pub fn write(&mut self, path: &Path, content: &[u8]) -> Result<(), LoadError> {
+        if let Some(parent) = path.parent() {
+            self.create_directories(parent)?;
+        }
+
+        if path.exists() {
+            self.overwriting(path, hash::digest(content))?;
+        } else {
+            self.creating(path, hash::digest(content))?;
+        }
+
+        return fs::write(path, content)
+            .map_err(|source| LoadError::Io { action: "write", path: path.to_path_buf(), source });
+    }
+
+    /// Backs up and removes the file at `path`.
+    pub fn remove(&mut self, path: &Path) -> Result<(), LoadError> {
+        self.back_up(path)?;
+        self.record(Record::Remove { path: self.relative(path) })?;
+
+        return fs::remove_file(path)
+            .map_err(|source| LoadError::Io { action: "remove", path: path.to_path_buf(), source });
+    }
+
+    /// Undoes every recorded change in reverse order and removes the journal, returning a line for each restored path.
+    pub fn rollback(self) -> Vec<String> {
+        let report = revert(&self.target, &self.directory, &self.records);
+
+        drop(self.file);
+        discard(&self.target, &self.directory);
+
+        return report;
+    }
+
+    fn record(&mut self, record: Record) -> Result<(), LoadError> {
+        let content = toml::to_string(&Appended { records: [&record] }).expect("Serialize journal record");
+        let path = self.directory.join(JOURNAL_FILE_NAME);
+
+        // Each record is written before the next change, so the journal is complete even if plat is killed
+        self.file.write_all(format!("\n{}", content).as_bytes())
+            .and_then(|_| self.file.sync_data())
+            .map_err(|source| LoadError::Io { action: "write journal", path, source })?;
+
+        self.records.push(record);
+
+        return Ok(());
+    }
+
+    fn relative(&self, path: &Path) -> PathBuf {
+        path.strip_prefix(&self.target).unwrap_or(path).to_path_buf()
+    }
+}
+
+impl Record {
+    pub fn path(&self) -> &Path {
+        match self {
+            Record::Directory { path } | Record::Remove { path } => path,
+            Record::File { path, .. } | Record::Overwrite { path, .. } => path,
+        }
+    }
+}
+
+/// Every load recorded in `target`, oldest first.
+pub fn history(target: &Path) -> Result<Vec<Recorded>, LoadError> {
+    let loads = target.join(STATE_DIRECTORY_NAME).join(LOADS_DIRECTORY_NAME);
+
+    if !loads.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut directories = fs::read_dir(&loads)
+        .and_then(|entries| entries.map(|entry| entry.map(|entry| entry.path())).collect::<Result<Vec<_>, _>>())
+        .map_err(|source| LoadError::Io { action: "read load history", path: loads.clone(), source })?;
+
+    directories.sort_by_key(|directory| order(directory));
+
+    let mut history = Vec::new();
+
+    for directory in directories {
+        let path = directory.join(JOURNAL_FILE_NAME);
+
+        let content = fs::read_to_string(&path)
+            .map_err(|source| LoadError::Io { action: "read journal", path: path.clone(), source })?;
+
+        let mut recorded: Recorded = toml::from_str(&content)
+            .map_err(|err| LoadError::Journal { path, message: err.message().to_string() })?;
+
+        recorded.directory = directory;
+        history.push(recorded);
+    }
+
+    return Ok(history);
+}
+
+/// Undoes `records` in reverse order, restoring overwritten files from the backups in `directory`.
+/// Failures are reported in the returned lines instead of stopping, so as much as possible is restored.
+pub fn revert(target: &Path, directory: &Path, records: &[Record]) -> Vec<String> {
+    let mut report = Vec::new();
+
+    for record in records.iter().rev() {
+        let (result, done) = match record {
+            Record::File { path, .. } => {
+                (fs::remove_file(target.join(path)), format!("removed {}", path.display()))
+            }
+
+            Record::Overwrite { path, .. } | Record::Remove { path } => {
+                let backup = directory.join(BACKUPS_DIRECTORY_NAME).join(path);
+                (copy::duplicate(&backup, &target.join(path)), format!("restored {}", path.display()))
+            }
+
+            Record::Directory { path } => {
+                (fs::remove_dir(target.join(path)), format!("removed {}/", path.display()))
+            }
+        };
+
+        match result {
+            Ok(()) => report.push(done),
+            Err(_) if matches!(record, Record::File { .. }) && !target.join(record.path()).exists() => {}
+            Err(err) => report.push(format!("could not restore {}: {}", record.path().display(), err)),
+        }
+    }
+
+    return report;
+}
+
+/// Removes a journal and its backups, along with the state directories if nothing else is left in them.
+pub fn discard(target: &Path, directory: &Path) {
+    let _ = fs::remove_dir_all(directory);
+
+    let loads = target.join(STATE_DIRECTORY_NAME).join(LOADS_DIRECTORY_NAME);
+    let _ = fs::remove_dir(&loads);
+    let _ = fs::remove_dir(target.join(STATE_DIRECTORY_NAME));
+}
+
+// Directories are named after the start time, with a numeric suffix for loads started in the same second
+fn order(directory: &Path) -> (String, u32) {
+    let name = directory.file_name().unwrap_or_default().to_string_lossy();
+
+    return match name.split_once("Z-") {
+        Some((started, index)) => (started.to_string(), index.parse().unwrap_or(0)),
+        None => (name.trim_end_matches('Z').to_string(), 1),
+    };
+}
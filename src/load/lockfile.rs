@@ -0,0 +1,140 @@
+use crate::load::copy::CopyOptions;
+use crate::load::error::LoadError;
+use crate::load::hash;
+use crate::load::ignore::IGNORE_FILE_NAME;
+use crate::load::journal::STATE_DIRECTORY_NAME;
+use crate::load::plan::{Action, Plan};
+use crate::task::answers::Answer;
+use crate::task::pipeline::{ENVIROMENT_FILE_NAME, TASK_FILE_NAME};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const LOCK_FILE_NAME: &str = ".plat-lock";
+pub const BASE_DIRECTORY_NAME: &str = "base";
+
+/// Records which template a project was generated from, written to `.plat-lock` in the target after each load.
+/// File paths use `/` as the separator on every platform. `edited` lists the files of the target a `write` rewrote in place,
+/// they are not the template's so they have no snapshot and are never removed. `copy` keeps the options the files were copied with,
+/// so updating and diffing produce the files the same way. `renamed` maps files the template generates to where they were
+/// written instead, because the user already had a file of their own there.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Lockfile {
+    pub template: String,
+    pub template_hash: String,
+    pub plat_version: String,
+    pub generated: String,
+    #[serde(default)]
+    pub answers: BTreeMap<String, Answer>,
+    #[serde(default)]
+    pub files: BTreeMap<String, String>,
+    #[serde(default)]
+    pub edited: BTreeSet<String>,
+    #[serde(default)]
+    pub copy: CopyOptions,
+    #[serde(default)]
+    pub renamed: BTreeMap<String, String>,
+}
+
+impl Lockfile {
+    /// The lockfile of `target`, if a template was loaded into it.
+    pub fn read(target: &Path) -> Result<Option<Lockfile>, LoadError> {
+        let path = target.join(LOCK_FILE_NAME);
+
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&path)
+            .map_err(|source| LoadError::Io { action: "read lockfile", path: path.clone(), source })?;
+
+        let lockfile = toml::from_str(&content)
+            .map_err(|err| LoadError::Lockfile { path, message: err.message().to_string() })?;
+
+        return Ok(Some(lockfile));
+    }
+
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).expect("Serialize lockfile")
+    }
+}
+
+/// The key of a file in the lockfile, its path relative to the target with `/` separators.
+pub fn file_key(relative: &Path) -> String {
+    relative.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Where the content a file had when it was last generated is kept, inside the target's state directory.
+pub fn base_path(target: &Path, key: &str) -> PathBuf {
+    let mut path = target.join(STATE_DIRECTORY_NAME).join(BASE_DIRECTORY_NAME);
+    path.extend(key.split('/'));
+
+    return path;
+}
+
+/// Hashes everything that makes up the template: the files the plan copies and the task, environment and ignore files.
+/// Each file contributes its path and the digest of its content, in a stable order.
+pub fn template_hash(origin: &Path, plan: &Plan) -> Result<String, LoadError> {
+    let controls: Vec<(String, Action)> = [TASK_FILE_NAME, ENVIROMENT_FILE_NAME, IGNORE_FILE_NAME].iter()
+        .filter(|name| origin.join(name).is_file())
+        .map(|name| (name.to_string(), Action::Copy(origin.join(name))))
+        .collect();
+
+    let mut files: Vec<(String, &Action)> = controls.iter()
+        .map(|(name, action)| (name.clone(), action))
+        .collect();
+
+    // Files the task rewrites in place belong to the target, not the template
+    for step in &plan.steps {
+        if !matches!(step.action, Action::CreateDirectory | Action::Edit { .. }) {
+            files.push((file_key(&step.path), &step.action));
+        }
+    }
+
+    files.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let mut summary = String::new();
+
+    for (key, action) in files {
+        let digest = action.digest()
+            .map_err(|source| LoadError::Io { action: "read template file", path: action.source(), source })?;
+
+        summary.push_str(&format!("{}\0{}\n", key, digest));
+    }
+
+    return Ok(hash::digest(summary.as_bytes()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Lockfile;
+    use crate::load::copy::SymlinkPolicy;
+
+    const RECORDED: &str = "template = \"t\"\ntemplate-hash = \"\"\nplat-version = \"0.1.0\"\ngenerated = \"\"\n";
+
+    #[test]
+    fn keeps_copy_options() {
+        let mut lockfile: Lockfile = toml::from_str(RECORDED).unwrap();
+
+        lockfile.copy.symlinks = SymlinkPolicy::Follow;
+        lockfile.copy.preserve_mtime = true;
+
+        let read: Lockfile = toml::from_str(&lockfile.to_toml()).unwrap();
+
+        assert_eq!(read.copy.symlinks, SymlinkPolicy::Follow);
+        assert!(read.copy.preserve_mtime);
+    }
+
+    #[test]
+    fn defaults_copy_options_of_older_lockfiles() {
+        let read: Lockfile = toml::from_str(RECORDED).unwrap();
+
+        assert_eq!(read.copy.symlinks, SymlinkPolicy::Keep);
+        assert!(!read.copy.preserve_mtime);
+    }
+}
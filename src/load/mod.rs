@@ -0,0 +1,417 @@
+pub mod conflict;
+pub mod copy;
+pub mod diff;
+pub mod error;
+pub mod hash;
+pub mod ignore;
+pub mod journal;
+pub mod lockfile;
+pub mod plan;
+pub mod preview;
+pub mod progress;
+pub mod questions;
+pub mod update;
+pub mod undo;
+
+use crate::load::conflict::{ConflictPolicy, ConflictResolver, Resolution};
+use crate::load::copy::CopyOptions;
+use crate::load::error::LoadError;
+use crate::load::ignore::IgnoreRules;
+use crate::load::journal::Journal;
+use crate::load::lockfile::{Lockfile, LOCK_FILE_NAME};
+use crate::load::plan::{Action, Plan};
+use crate::load::preview::Change;
+use crate::load::progress::Progress;
+use crate::prompt::Prompter;
+use crate::task::answers::Answers;
+use crate::task::context::Context;
+use crate::task::error::Error;
+use crate::task::layers::evaluate::{self, Rewrite, Scope};
+use crate::task::nodes::collection::NodeCollection;
+use crate::task::pipeline::{parse_enviroment_file, parse_task, ENVIROMENT_FILE_NAME, TASK_FILE_NAME};
+use crate::timestamp;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+pub struct LoadOptions {
+    pub conflict: ConflictPolicy,
+    pub copy: CopyOptions,
+}
+
+/// The files a load or update leaves in the target from the template, keyed like the lockfile.
+/// `renamed` maps the key of a planned file to the key it was written to instead, beside a file of the user's own.
+pub struct Generated<'a> {
+    pub files: BTreeMap<String, &'a Action>,
+    pub renamed: BTreeMap<String, String>,
+}
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Applies the template at `origin` to `target`, rendered with the answers in `context`. Every change is journaled,
+/// and when the load fails or is interrupted with Ctrl-C the target is restored to the state it was in before the load.
+pub fn load(name: &str, origin: &Path, target: &Path, context: &Context, options: &LoadOptions, prompter: &Prompter) -> Result<(), LoadError> {
+    let (plan, rewrites) = build_plan(origin, target, context, &options.copy)?;
+
+    let template_hash = lockfile::template_hash(origin, &plan)?;
+    let mut resolver = ConflictResolver::new(options.conflict, prompter);
+    let mut progress = Progress::new(&plan);
+
+    transaction(name, target, |journal| {
+        let result = apply(&plan, target, &options.copy, journal, &mut resolver, &mut progress)
+            .and_then(|generated| record_generation(name, template_hash, context.answers(), &options.copy, &generated, target, journal));
+
+        match result {
+            Ok(()) => progress.finish(),
+            Err(_) => progress.abandon(),
+        }
+
+        return result;
+    })?;
+
+    for rewrite in &rewrites {
+        println!("  {}", rewrite);
+    }
+
+    return Ok(());
+}
+
+/// Runs `changes` against a journal of `target`. When it fails, or is interrupted with Ctrl-C,
+/// every journaled change is rolled back and the target is left the way it was before.
+pub fn transaction<T>(name: &str, target: &Path, changes: impl FnOnce(&mut Journal) -> Result<T, LoadError>) -> Result<T, LoadError> {
+    let created_target = !target.exists();
+
+    fs::create_dir_all(target)
+        .map_err(|source| LoadError::Io { action: "create target directory", path: target.to_path_buf(), source })?;
+
+    // Setting the handler only fails when one is already set, in which case it is already ours
+    let _ = ctrlc::set_handler(|| INTERRUPTED.store(true, Ordering::SeqCst));
+
+    let mut journal = Journal::begin(target, name)?;
+    let result = changes(&mut journal);
+
+    if result.is_ok() {
+        return result;
+    }
+
+    println!("Failed, restoring '{}' to its previous state", target.display());
+
+    for line in journal.rollback() {
+        println!("  {}", line);
+    }
+
+    if created_target {
+        let _ = fs::remove_dir(target);
+    }
+
+    return result;
+}
+
+/// Whether Ctrl-C was pressed during a transaction, long running changes should stop with `LoadError::Interrupted`.
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+/// Runs the same planning as `load` and reports what it would change and the replacements it would make,
+/// without touching the disk.
+pub fn dry_run(origin: &Path, target: &Path, context: &Context, options: &LoadOptions) -> Result<(Vec<Change>, Vec<Rewrite>), LoadError> {
+    let (plan, rewrites) = build_plan(origin, target, context, &options.copy)?;
+
+    return Ok((preview::preview(&plan, target, options.conflict)?, rewrites));
+}
+
+/// Parses the `.platenv` and `task.plat` of the template at `origin`, then asks the questions `given` does not answer.
+/// Syntax errors in both files are reported together, before anything is asked.
+pub fn prepare(origin: &Path, given: Answers, prompter: &Prompter) -> Result<Context, LoadError> {
+    let enviroment = read_control_file(origin, ENVIROMENT_FILE_NAME)?;
+    let task = read_control_file(origin, TASK_FILE_NAME)?;
+
+    let mut errors = Vec::new();
+
+    let statements = match enviroment.as_deref().map(parse_enviroment_file) {
+        Some(NodeCollection::Ok(statements)) => statements,
+        Some(NodeCollection::Failed(failed)) => {
+            errors.extend(failed.into_iter().map(|error| (ENVIROMENT_FILE_NAME, error)));
+            Vec::new()
+        }
+        None => Vec::new(),
+    };
+
+    if let Some(NodeCollection::Failed(failed)) = task.as_deref().map(parse_task) {
+        errors.extend(failed.into_iter().map(|error| (TASK_FILE_NAME, error)));
+    }
+
+    if !errors.is_empty() {
+        return Err(LoadError::Template(errors));
+    }
+
+    let mut context = Context::new(given);
+    questions::ask(&statements, &mut context, prompter)?;
+
+    return Ok(context);
+}
+
+/// Reads the answers to a template's questions from a toml file, as given with `--answers`.
+pub fn read_answers(path: &Path) -> Result<Answers, LoadError> {
+    let content = fs::read_to_string(path)
+        .map_err(|source| LoadError::Io { action: "read answers file", path: path.to_path_buf(), source })?;
+
+    return Answers::parse(&content)
+        .map_err(|message| LoadError::Answers { path: path.to_path_buf(), message });
+}
+
+/// Plans what loading the template at `origin` into `target` does. Without a `task.plat` every file that is not ignored
+/// is copied, otherwise the task is evaluated with `context` and every error it has is reported together.
+/// Returns the replacements the task's `write` instructions make along with the plan.
+pub fn build_plan(origin: &Path, target: &Path, context: &Context, copy: &CopyOptions) -> Result<(Plan, Vec<Rewrite>), LoadError> {
+    let rules = IgnoreRules::read(origin)?;
+
+    let Some(content) = read_control_file(origin, TASK_FILE_NAME)? else {
+        return Ok((Plan::from_directory(origin, &rules, copy.symlinks)?, Vec::new()));
+    };
+
+    let instructions = match parse_task(&content) {
+        NodeCollection::Ok(instructions) => instructions,
+        NodeCollection::Failed(errors) => return Err(task_errors(errors)),
+    };
+
+    let edited = Lockfile::read(target)?.map(|lockfile| lockfile.edited).unwrap_or_default();
+
+    let mut plan = Plan::new();
+    let scope = Scope { origin, target, context, rules: &rules, copy, edited: &edited };
+
+    let rewrites = evaluate::evaluate(&instructions, &scope, &mut plan).map_err(task_errors)?;
+
+    return Ok((plan, rewrites));
+}
+
+// The content of `.platenv` or `task.plat`, `None` when the template does not have one
+fn read_control_file(origin: &Path, file_name: &str) -> Result<Option<String>, LoadError> {
+    let path = origin.join(file_name);
+
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|source| LoadError::Io { action: "read", path, source })?;
+
+    return Ok(Some(content));
+}
+
+fn task_errors(errors: Vec<Error>) -> LoadError {
+    LoadError::Template(errors.into_iter().map(|error| (TASK_FILE_NAME, error)).collect())
+}
+
+/// Applies each step of the plan, returning the action behind every file the target now has from the template.
+fn apply<'a>(plan: &'a Plan, target: &Path, copy: &CopyOptions, journal: &mut Journal, resolver: &mut ConflictResolver, progress: &mut Progress) -> Result<Generated<'a>, LoadError> {
+    let mut generated = Generated { files: BTreeMap::new(), renamed: BTreeMap::new() };
+
+    for step in &plan.steps {
+        if interrupted() {
+            return Err(LoadError::Interrupted);
+        }
+
+        let mut path = target.join(&step.path);
+
+        if let Action::CreateDirectory = step.action {
+            check_destination(target, &path, &step.action)?;
+            journal.create_directories(&path)?;
+            continue;
+        }
+
+        // A file rewritten in place is the user's own, changing it is what the task asks for
+        if (path.exists() || path.is_symlink()) && !matches!(step.action, Action::Edit { .. }) {
+            let existing = plan::read_existing(&path, &step.action)
+                .map_err(|source| LoadError::Io { action: "read existing file", path: path.clone(), source })?;
+            let incoming = step.action.read_content()
+                .map_err(|source| LoadError::Io { action: "read template file", path: step.action.source(), source })?;
+
+            // Files that already have the right content are not conflicts
+            if existing == incoming {
+                generated.files.insert(lockfile::file_key(&step.path), &step.action);
+                progress.advance(&step.path, incoming.len() as u64);
+                continue;
+            }
+
+            match progress.suspend(|| resolver.resolve(&path, &step.path, &existing, &incoming, step.conflict))? {
+                Resolution::Overwrite => {}
+                Resolution::Skip => {
+                    progress.advance(&step.path, incoming.len() as u64);
+                    continue;
+                }
+                Resolution::Rename(renamed) => path = renamed,
+            }
+        }
+
+        check_destination(target, &path, &step.action)?;
+
+        if let Some(parent) = path.parent() {
+            journal.create_directories(parent)?;
+        }
+
+        let size = write_step(&step.action, &path, copy, journal)?;
+
+        let key = lockfile::file_key(path.strip_prefix(target).unwrap_or(&path));
+
+        // The file at the planned path is the user's own, updates follow the template's file to where it was renamed
+        if key != lockfile::file_key(&step.path) {
+            generated.renamed.insert(lockfile::file_key(&step.path), key.clone());
+        }
+
+        generated.files.insert(key, &step.action);
+        progress.advance(&step.path, size);
+    }
+
+    return Ok(generated);
+}
+
+/// Fails when writing `action` to `path` would leave `target` through a symlink. The task checks its paths when it is
+/// evaluated, this catches symlinks created by earlier steps or by anything else while the load runs.
+pub fn check_destination(target: &Path, path: &Path, action: &Action) -> Result<(), LoadError> {
+    // Symlinks at the destination itself are replaced, except by steps that write through them
+    let checked = match action {
+        Action::CreateDirectory | Action::Edit { .. } => path,
+        _ => path.parent().unwrap_or(path),
+    };
+
+    let resolved = resolve(checked);
+
+    if !resolved.starts_with(resolve(target)) {
+        return Err(LoadError::OutsideTarget { path: path.to_path_buf(), resolved });
+    }
+
+    return Ok(());
+}
+
+/// Journals and performs a file step, writing to `path` with the copy engine.
+pub fn write_step(action: &Action, path: &Path, copy: &CopyOptions, journal: &mut Journal) -> Result<u64, LoadError> {
+    let hash = action.digest()
+        .map_err(|source| LoadError::Io { action: "read template file", path: action.source(), source })?;
+
+    if path.exists() || path.is_symlink() {
+        journal.overwriting(path, hash)?;
+    } else {
+        journal.creating(path, hash)?;
+    }
+
+    return action.write_to(path, copy)
+        .map_err(|source| LoadError::Io { action: "copy file to", path: path.to_path_buf(), source });
+}
+
+/// Writes the lockfile and a snapshot of every generated file into the target, the snapshots are the base `plat update` merges against.
+/// Snapshots of files the template no longer generates are removed.
+pub fn record_generation(name: &str, template_hash: String, answers: &Answers, copy: &CopyOptions, generated: &Generated, target: &Path, journal: &mut Journal) -> Result<(), LoadError> {
+    let previous = Lockfile::read(target)?;
+    let mut files = BTreeMap::new();
+    let mut edited = BTreeSet::new();
+
+    for (key, action) in &generated.files {
+        if let Action::Edit { .. } = action {
+            edited.insert(key.clone());
+            continue;
+        }
+
+        let content = action.read_content()
+            .map_err(|source| LoadError::Io { action: "read template file", path: action.source(), source })?;
+
+        journal.write(&lockfile::base_path(target, key), &content)?;
+        files.insert(key.clone(), hash::digest(&content));
+    }
+
+    for key in previous.iter().flat_map(|lockfile| lockfile.files.keys()) {
+        let base = lockfile::base_path(target, key);
+
+        if !files.contains_key(key) && base.is_file() {
+            journal.remove(&base)?;
+        }
+    }
+
+    let lockfile = Lockfile {
+        template: name.to_string(),
+        template_hash,
+        plat_version: env!("CARGO_PKG_VERSION").to_string(),
+        generated: timestamp::now(),
+        answers: answers.recordable(),
+        files,
+        edited,
+        copy: *copy,
+        renamed: generated.renamed.clone(),
+    };
+
+    return journal.write(&target.join(LOCK_FILE_NAME), lockfile.to_toml().as_bytes());
+}
+
+/// Fails when `target` is the template directory or one of its subdirectories,
+/// which would make the copy recurse into its own output.
+pub fn check_target(origin: &Path, target: &Path) -> Result<(), LoadError> {
+    let origin = fs::canonicalize(origin)
+        .map_err(|source| LoadError::Io { action: "resolve template directory", path: origin.to_path_buf(), source })?;
+
+    if resolve(target).starts_with(&origin) {
+        return Err(LoadError::IntoSource { origin, target: target.to_path_buf() });
+    }
+
+    return Ok(());
+}
+
+/// Whether `target` is missing or contains no entries.
+pub fn is_empty(target: &Path) -> bool {
+    fs::read_dir(target).map_or(true, |mut entries| entries.next().is_none())
+}
+
+/// `path` with every symlink resolved. Only the longest existing ancestor is canonicalized, since the target may not
+/// have been created yet, the parts that do not exist are appended as they are.
+pub(crate) fn resolve(path: &Path) -> PathBuf {
+    let mut existing = path;
+    let mut rest: Vec<&std::ffi::OsStr> = Vec::new();
+
+    while fs::canonicalize(existing).is_err() {
+        let (Some(parent), Some(name)) = (existing.parent(), existing.file_name()) else {
+            return path.to_path_buf();
+        };
+
+        rest.push(name);
+        existing = parent;
+    }
+
+    let mut resolved = fs::canonicalize(existing).unwrap_or_else(|_| existing.to_path_buf());
+
+    for name in rest.iter().rev() {
+        resolved.push(name);
+    }
+
+    return resolved;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check_destination;
+    use crate::load::plan::Action;
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_destination_behind_target_symlink() {
+        let root = std::env::temp_dir().join(format!("plat-load-{}-destination", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+
+        let target = root.join("target");
+        let outside = root.join("outside");
+
+        fs::create_dir_all(target.join("dir")).expect("Create target directory");
+        fs::create_dir_all(&outside).expect("Create outside directory");
+        std::os::unix::fs::symlink(&outside, target.join("dir/sub")).expect("Create symlink");
+
+        let copy = Action::Copy(PathBuf::from("file"));
+
+        assert!(check_destination(&target, &target.join("dir/file"), &copy).is_ok());
+        assert!(check_destination(&target, &target.join("dir/sub/file"), &copy).is_err());
+        assert!(check_destination(&target, &target.join("dir/sub"), &Action::CreateDirectory).is_err());
+
+        // A file step replaces the symlink itself rather than writing through it
+        assert!(check_destination(&target, &target.join("dir/sub"), &copy).is_ok());
+    }
+}
@@ -0,0 +1,219 @@
+use crate::load::conflict::ConflictPolicy;
+use crate::load::copy::{self, CopyOptions, SymlinkPolicy};
+use crate::load::error::LoadError;
+use crate::load::hash;
+use crate::load::ignore::IgnoreRules;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub enum Action {
+    CreateDirectory,
+    Copy(PathBuf),
+    Symlink(PathBuf),
+    /// A file whose content was produced while evaluating the task, `source` is the file it was derived from.
+    Generate { source: PathBuf, content: Vec<u8> },
+    /// A file of the target rewritten in place by the task, `path` is where it was read from.
+    /// The file belongs to the user rather than the template, so rewriting it is never a conflict.
+    Edit { path: PathBuf, content: Vec<u8> },
+}
+
+impl Action {
+    /// The content the step is going to write, empty for directories and the link for symlinks.
+    pub fn read_content(&self) -> io::Result<Vec<u8>> {
+        match self {
+            Action::CreateDirectory => Ok(Vec::new()),
+            Action::Copy(source) => fs::read(source),
+            Action::Symlink(link) => Ok(link.as_os_str().as_encoded_bytes().to_vec()),
+            Action::Generate { content, .. } | Action::Edit { content, .. } => Ok(content.clone()),
+        }
+    }
+
+    /// The number of bytes the step is going to write.
+    pub fn size(&self) -> u64 {
+        match self {
+            Action::CreateDirectory => 0,
+            Action::Copy(source) => fs::metadata(source).map_or(0, |metadata| metadata.len()),
+            Action::Symlink(link) => link.as_os_str().len() as u64,
+            Action::Generate { content, .. } | Action::Edit { content, .. } => content.len() as u64,
+        }
+    }
+
+    /// The digest of the content the step is going to write.
+    pub fn digest(&self) -> io::Result<String> {
+        match self {
+            Action::Copy(source) => hash::digest_file(source),
+            _ => Ok(hash::digest(&self.read_content()?)),
+        }
+    }
+
+    /// The template file the step reads, for error messages.
+    pub fn source(&self) -> PathBuf {
+        match self {
+            Action::Copy(source) => source.clone(),
+            Action::Symlink(link) => link.clone(),
+            Action::Generate { source, .. } => source.clone(),
+            Action::Edit { path, .. } => path.clone(),
+            Action::CreateDirectory => PathBuf::new(),
+        }
+    }
+
+    /// Writes the result of a file step to `destination` with the copy engine, returning the number of bytes written.
+    pub fn write_to(&self, destination: &Path, options: &CopyOptions) -> io::Result<u64> {
+        match self {
+            Action::CreateDirectory => Ok(0),
+            Action::Copy(source) => copy::copy_file(source, destination, options),
+            // The link itself is what gets written, counted the same way `size` counts it
+            Action::Symlink(link) => copy::create_symlink(link, destination).map(|_| link.as_os_str().len() as u64),
+            Action::Generate { source, content } => copy::write_file(source, content, destination),
+            Action::Edit { content, .. } => fs::write(destination, content).map(|_| content.len() as u64),
+        }
+    }
+}
+
+/// The content at `path` in the same form `Action::read_content` gives for the step that produces it,
+/// so a symlink that already points to the right place compares as identical.
+pub fn read_existing(path: &Path, action: &Action) -> io::Result<Vec<u8>> {
+    if let Action::Symlink(_) = action {
+        if let Ok(link) = fs::read_link(path) {
+            return Ok(link.as_os_str().as_encoded_bytes().to_vec());
+        }
+    }
+
+    return fs::read(path);
+}
+
+/// A single change to the target, `path` is relative to the target directory.
+/// `conflict` overrides the load's conflict policy for this step only.
+pub struct Step {
+    pub path: PathBuf,
+    pub action: Action,
+    pub conflict: Option<ConflictPolicy>,
+}
+
+/// Everything a load is going to do to the target, collected before anything is touched.
+pub struct Plan {
+    pub steps: Vec<Step>,
+}
+
+impl Plan {
+    pub fn new() -> Self {
+        Plan { steps: Vec::new() }
+    }
+
+    pub fn push(&mut self, path: PathBuf, action: Action, conflict: Option<ConflictPolicy>) {
+        self.steps.push(Step { path, action, conflict });
+    }
+
+    /// The number of steps that write a file.
+    pub fn file_count(&self) -> usize {
+        self.steps.iter().filter(|step| !matches!(step.action, Action::CreateDirectory)).count()
+    }
+
+    /// The combined size of every file the plan writes, files that can not be read count as empty.
+    pub fn total_size(&self) -> u64 {
+        self.steps.iter().map(|step| step.action.size()).sum()
+    }
+
+    /// Plans a copy of every file in `origin` that is not ignored, keeping the directory layout.
+    /// Symlinks are recreated, followed or rejected according to `symlinks`.
+    pub fn from_directory(origin: &Path, rules: &IgnoreRules, symlinks: SymlinkPolicy) -> Result<Plan, LoadError> {
+        let mut plan = Plan::new();
+
+        plan.add_directory(origin, Path::new(""), Path::new(""), rules, symlinks, None)?;
+
+        return Ok(plan);
+    }
+
+    /// Plans a copy of the contents of the directory `relative` in `origin` into `destination` in the target,
+    /// leaving out ignored entries.
+    pub fn add_directory(&mut self, origin: &Path, relative: &Path, destination: &Path, rules: &IgnoreRules, symlinks: SymlinkPolicy, conflict: Option<ConflictPolicy>) -> Result<(), LoadError> {
+        let directory = origin.join(relative);
+
+        let entries = fs::read_dir(&directory)
+            .map_err(|source| LoadError::Io { action: "read template directory", path: directory.clone(), source })?;
+
+        let mut entries = entries.collect::<Result<Vec<_>, _>>()
+            .map_err(|source| LoadError::Io { action: "read template directory", path: directory.clone(), source })?;
+
+        entries.sort_by_key(|entry| entry.file_name());
+
+        for entry in entries {
+            let child = relative.join(entry.file_name());
+
+            if rules.is_ignored(&child, entry.path().is_dir()) {
+                continue;
+            }
+
+            self.add_entry(origin, &child, destination.join(entry.file_name()), rules, symlinks, conflict)?;
+        }
+
+        return Ok(());
+    }
+
+    /// Plans a copy of the file, symlink or directory `relative` in `origin` to `destination` in the target.
+    /// A symlink that is followed has to point to somewhere inside `origin`.
+    pub fn add_entry(&mut self, origin: &Path, relative: &Path, destination: PathBuf, rules: &IgnoreRules, symlinks: SymlinkPolicy, conflict: Option<ConflictPolicy>) -> Result<(), LoadError> {
+        let path = origin.join(relative);
+
+        if path.is_symlink() {
+            match symlinks {
+                SymlinkPolicy::Follow => check_inside(origin, &path)?,
+                SymlinkPolicy::Reject => return Err(LoadError::Symlink(path)),
+
+                SymlinkPolicy::Keep => {
+                    let link = fs::read_link(&path)
+                        .map_err(|source| LoadError::Io { action: "read symlink", path: path.clone(), source })?;
+
+                    self.push(destination, Action::Symlink(link), conflict);
+                    return Ok(());
+                }
+            }
+        }
+
+        if path.is_dir() {
+            self.push(destination.clone(), Action::CreateDirectory, conflict);
+            return self.add_directory(origin, relative, &destination, rules, symlinks, conflict);
+        }
+
+        self.push(destination, Action::Copy(path), conflict);
+
+        return Ok(());
+    }
+}
+
+// Fails when the symlink at `path` leads out of the template, following it would copy files the template does not have
+fn check_inside(origin: &Path, path: &Path) -> Result<(), LoadError> {
+    let canonical = |path: &Path| fs::canonicalize(path)
+        .map_err(|source| LoadError::Io { action: "resolve", path: path.to_path_buf(), source });
+
+    let resolved = canonical(path)?;
+
+    if !resolved.starts_with(canonical(origin)?) {
+        return Err(LoadError::SymlinkOutside { path: path.to_path_buf(), resolved });
+    }
+
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Action;
+    use crate::load::copy::CopyOptions;
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[cfg(unix)]
+    #[test]
+    fn symlink_writes_its_size() {
+        let target = std::env::temp_dir().join(format!("plat-plan-{}-symlink", std::process::id()));
+        let _ = fs::remove_dir_all(&target);
+        fs::create_dir_all(&target).expect("Create target directory");
+
+        let action = Action::Symlink(PathBuf::from("a.txt"));
+        let written = action.write_to(&target.join("link"), &CopyOptions::default()).ok();
+
+        assert_eq!(written, Some(action.size()));
+        assert_eq!(action.size(), action.read_content().unwrap().len() as u64);
+    }
+}
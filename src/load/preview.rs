@@ -0,0 +1,144 @@
+use crate::load::conflict::ConflictPolicy;
+use crate::load::error::LoadError;
+use crate::load::plan::{self, Action, Plan};
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+/// What a step of the plan would do to the target.
+pub enum ChangeKind {
+    Create,
+    Overwrite,
+    Edit,
+    Rename(PathBuf),
+    Skip,
+    Unchanged,
+    Ask,
+    Fail,
+}
+
+impl ChangeKind {
+    fn label(&self) -> &'static str {
+        match self {
+            ChangeKind::Create => "create",
+            ChangeKind::Overwrite => "overwrite",
+            ChangeKind::Edit => "edit",
+            ChangeKind::Rename(_) => "rename",
+            ChangeKind::Skip => "skip",
+            ChangeKind::Unchanged => "unchanged",
+            ChangeKind::Ask => "ask",
+            ChangeKind::Fail => "fail",
+        }
+    }
+}
+
+/// A step of the plan as it would apply to the target, `size` is unset for directories.
+pub struct Change {
+    pub path: PathBuf,
+    pub kind: ChangeKind,
+    pub size: Option<u64>,
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let separator = if self.size.is_none() { "/" } else { "" };
+
+        write!(f, "{:<10} {}{}", self.kind.label(), self.path.display(), separator)?;
+
+        if let ChangeKind::Rename(renamed) = &self.kind {
+            write!(f, " -> {}", renamed.display())?;
+        }
+
+        if let Some(size) = self.size {
+            write!(f, " ({})", format_size(size))?;
+        }
+
+        match self.kind {
+            ChangeKind::Ask => write!(f, ", already exists and will be asked about"),
+            ChangeKind::Fail => write!(f, ", already exists and will fail the load"),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Works out what applying `plan` to `target` would do, without writing anything or asking questions.
+pub fn preview(plan: &Plan, target: &Path, default: ConflictPolicy) -> Result<Vec<Change>, LoadError> {
+    let mut changes = Vec::new();
+
+    for step in &plan.steps {
+        let path = target.join(&step.path);
+
+        let (kind, size) = match &step.action {
+            Action::CreateDirectory if path.is_dir() => continue,
+            Action::CreateDirectory => (ChangeKind::Create, None),
+
+            action => {
+                let incoming = action.read_content()
+                    .map_err(|source| LoadError::Io { action: "read template file", path: action.source(), source })?;
+
+                let kind = if !path.exists() && !path.is_symlink() {
+                    ChangeKind::Create
+                } else if plan::read_existing(&path, action).is_ok_and(|existing| existing == incoming) {
+                    ChangeKind::Unchanged
+                } else if let Action::Edit { .. } = action {
+                    ChangeKind::Edit
+                } else {
+                    match step.conflict.unwrap_or(default) {
+                        ConflictPolicy::Skip => ChangeKind::Skip,
+                        ConflictPolicy::Overwrite => ChangeKind::Overwrite,
+                        ConflictPolicy::Rename => ChangeKind::Rename(renamed(&step.path)),
+                        ConflictPolicy::Prompt => ChangeKind::Ask,
+                        ConflictPolicy::Fail => ChangeKind::Fail,
+                    }
+                };
+
+                (kind, Some(incoming.len() as u64))
+            }
+        };
+
+        changes.push(Change { path: step.path.clone(), kind, size });
+    }
+
+    return Ok(changes);
+}
+
+/// A one line count of the changes, like `3 to create, 1 to overwrite, 0 to edit, 0 in conflict, 2 skipped`.
+pub fn summary(changes: &[Change]) -> String {
+    let count = |predicate: fn(&ChangeKind) -> bool| changes.iter().filter(|change| predicate(&change.kind)).count();
+
+    let counts = [
+        (count(|kind| matches!(kind, ChangeKind::Create | ChangeKind::Rename(_))), "to create"),
+        (count(|kind| matches!(kind, ChangeKind::Overwrite)), "to overwrite"),
+        (count(|kind| matches!(kind, ChangeKind::Edit)), "to edit"),
+        (count(|kind| matches!(kind, ChangeKind::Ask | ChangeKind::Fail)), "in conflict"),
+        (count(|kind| matches!(kind, ChangeKind::Skip | ChangeKind::Unchanged)), "skipped"),
+    ];
+
+    return counts.iter()
+        .map(|(count, label)| format!("{} {}", count, label))
+        .collect::<Vec<_>>()
+        .join(", ");
+}
+
+// The rename policy picks the first free name when the file is written, this shows the usual one
+fn renamed(path: &Path) -> PathBuf {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    return path.with_file_name(format!("{}.plat-new", name));
+}
+
+pub fn format_size(size: u64) -> String {
+    const UNITS: &[&str] = &["KiB", "MiB", "GiB"];
+
+    if size < 1024 {
+        return format!("{} B", size);
+    }
+
+    let mut value = size as f64 / 1024.0;
+    let mut unit = 0;
+
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    return format!("{:.1} {}", value, UNITS[unit]);
+}
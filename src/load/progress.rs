@@ -0,0 +1,75 @@
+use crate::load::plan::Plan;
+use crate::load::preview::format_size;
+use indicatif::{ProgressBar, ProgressStyle};
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use std::time::Instant;
+
+const TEMPLATE: &str = "{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {bytes}/{total_bytes} ({bytes_per_sec}, {eta}) {msg}";
+
+/// Reports how far a load is, as a progress bar on a terminal and as one line per file otherwise.
+pub struct Progress {
+    bar: Option<ProgressBar>,
+    files: usize,
+    total_files: usize,
+    total_bytes: u64,
+    started: Instant,
+}
+
+impl Progress {
+    pub fn new(plan: &Plan) -> Self {
+        let total_files = plan.file_count();
+        let total_bytes = plan.total_size();
+
+        let bar = io::stdout().is_terminal().then(|| {
+            let style = ProgressStyle::with_template(TEMPLATE)
+                .expect("Parse progress bar template")
+                .progress_chars("=> ");
+
+            ProgressBar::new(total_bytes).with_style(style)
+        });
+
+        Progress { bar, files: 0, total_files, total_bytes, started: Instant::now() }
+    }
+
+    /// Marks a file of `size` bytes at `path` as done.
+    pub fn advance(&mut self, path: &Path, size: u64) {
+        self.files += 1;
+
+        match &self.bar {
+            Some(bar) => {
+                bar.set_message(format!("{}/{} {}", self.files, self.total_files, path.display()));
+                bar.inc(size);
+            }
+
+            None => println!("[{}/{}] {} ({})", self.files, self.total_files, path.display(), format_size(size)),
+        }
+    }
+
+    /// Hides the bar while `action` runs, so prompts and messages are not drawn over.
+    pub fn suspend<T>(&self, action: impl FnOnce() -> T) -> T {
+        match &self.bar {
+            Some(bar) => bar.suspend(action),
+            None => action(),
+        }
+    }
+
+    pub fn finish(&self) {
+        if let Some(bar) = &self.bar {
+            bar.finish_and_clear();
+        }
+
+        println!("Processed {} file(s), {} in {:.1}s",
+                 self.files,
+                 format_size(self.total_bytes),
+                 self.started.elapsed().as_secs_f64()
+        );
+    }
+
+    /// Removes the bar without a summary, for loads that stop early.
+    pub fn abandon(&self) {
+        if let Some(bar) = &self.bar {
+            bar.abandon();
+        }
+    }
+}
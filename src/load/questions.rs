@@ -0,0 +1,175 @@
+use crate::load::error::LoadError;
+use crate::prompt::Prompter;
+use crate::task::answers::Answer;
+use crate::task::context::Context;
+use crate::task::error::Error;
+use crate::task::layers::parsers::enviroment::{FieldType, MatchPattern, Statement, Validator};
+use crate::task::nodes::node::Node;
+use crate::task::pipeline::ENVIROMENT_FILE_NAME;
+use crate::task::position::Position;
+use crate::task::value::number::NumberValue;
+use crate::task::value::range::RangeValue;
+use crate::task::value::Value;
+use regex::Regex;
+
+const ANSWERS_HINT: &str = "pass the answers in a toml file with --answers";
+
+/// Asks the questions of `.platenv` in order, adding the answers to `context`. Questions `context` already
+/// has an answer for are not asked, the given answer is checked against the question instead.
+/// A `match` only asks the questions of the first case matching the answer it looks at.
+pub fn ask(statements: &[Node<Statement>], context: &mut Context, prompter: &Prompter) -> Result<(), LoadError> {
+    for Node { data, position } in statements {
+        match data {
+            Statement::Declaration { identifier, field_type, prompt, validator } => {
+                let rule = Rule::new(field_type, validator.as_ref(), context, position)?;
+                let secret = matches!(field_type, FieldType::Secret);
+
+                let answer = match context.answers().get(identifier) {
+                    Some(given) => rule.parse(&given.to_string()).map_err(|message| invalid(
+                        format!("The answer '{}' to '${}' is invalid, {}", given, identifier, message),
+                        position,
+                    ))?,
+
+                    None => {
+                        let prompt = context.render_expression(prompt).map_err(enviroment_error)?;
+                        rule.ask(&prompt, secret, prompter)?
+                    }
+                };
+
+                context.insert(identifier, answer, secret);
+            }
+
+            Statement::Match { identifier, cases } => {
+                let answer = context.get(identifier, position)
+                    .map_err(enviroment_error)?
+                    .clone();
+
+                let case = cases.iter().find(|case| case.patterns.iter().any(|pattern| matches(pattern, &answer, context)));
+
+                if let Some(case) = case {
+                    ask(&case.expression, context, prompter)?;
+                }
+            }
+        }
+    }
+
+    return Ok(());
+}
+
+// What an answer to a single question has to look like
+struct Rule<'a> {
+    field_type: &'a FieldType,
+    range: Option<(i32, i32)>,
+    regex: Option<Regex>,
+    options: Vec<String>,
+}
+
+impl<'a> Rule<'a> {
+    fn new(field_type: &'a FieldType, validator: Option<&Validator>, context: &Context, position: &Position) -> Result<Rule<'a>, LoadError> {
+        let mut rule = Rule { field_type, range: None, regex: None, options: Vec::new() };
+
+        match validator {
+            Some(Validator::Range(begin, end)) => rule.range = Some((*begin, *end)),
+
+            Some(Validator::Regex(regex)) => {
+                let regex = Regex::new(regex)
+                    .map_err(|_| invalid(format!("/{}/ is not a valid regex", regex), position))?;
+
+                rule.regex = Some(regex);
+            }
+
+            Some(Validator::Switch(options)) => {
+                for option in options.iter() {
+                    let option = context.render_expression(option)
+                        .map_err(enviroment_error)?;
+
+                    rule.options.push(option);
+                }
+            }
+
+            None => {}
+        }
+
+        return Ok(rule);
+    }
+
+    fn ask(&self, prompt: &str, secret: bool, prompter: &Prompter) -> Result<Answer, LoadError> {
+        let validate = |input: &String| self.parse(input).map(|_| ());
+
+        let input = if !self.options.is_empty() {
+            let items = self.options.iter().map(String::as_str).collect::<Vec<_>>();
+            let index = prompter.select(prompt, &items, ANSWERS_HINT).map_err(LoadError::Prompt)?;
+
+            self.options[index].clone()
+        } else if secret {
+            prompter.password(prompt, ANSWERS_HINT, validate).map_err(LoadError::Prompt)?
+        } else {
+            prompter.input(prompt, ANSWERS_HINT, validate).map_err(LoadError::Prompt)?
+        };
+
+        return Ok(self.parse(&input).expect("Parse validated answer"));
+    }
+
+    /// The typed answer `input` stands for, or why it does not answer the question.
+    fn parse(&self, input: &str) -> Result<Answer, String> {
+        if let Some(regex) = self.regex.as_ref().filter(|regex| !regex.is_match(input)) {
+            return Err(format!("it has to match /{}/", regex));
+        }
+
+        if !self.options.is_empty() && !self.options.iter().any(|option| option == input) {
+            return Err(format!("it has to be one of {}", self.options.join(", ")));
+        }
+
+        let answer = match self.field_type {
+            FieldType::Integer => Answer::Integer(input.trim().parse().map_err(|_| String::from("it has to be a whole number"))?),
+            FieldType::Decimal => Answer::Decimal(input.trim().parse().map_err(|_| String::from("it has to be a number"))?),
+            FieldType::Text | FieldType::Switch | FieldType::Secret => Answer::Text(input.to_string()),
+        };
+
+        let number = match answer {
+            Answer::Integer(number) => number as f32,
+            Answer::Decimal(number) => number,
+            Answer::Text(_) => return Ok(answer),
+        };
+
+        if let Some((begin, end)) = self.range {
+            if number < begin as f32 || number > end as f32 {
+                return Err(format!("it has to be between {} and {}", begin, end));
+            }
+        }
+
+        return Ok(answer);
+    }
+}
+
+// Ranges include both ends, strings are compared with the display form of the answer
+fn matches(pattern: &MatchPattern, answer: &Answer, context: &Context) -> bool {
+    match pattern {
+        MatchPattern::Any => true,
+        MatchPattern::Variable(identifier) => context.answers().get(identifier) == Some(answer),
+        MatchPattern::Value(Value::String(expression)) => context.render_expression(expression).is_ok_and(|text| text == answer.to_string()),
+        MatchPattern::Value(Value::Regex(regex)) => Regex::new(regex).is_ok_and(|regex| regex.is_match(&answer.to_string())),
+
+        MatchPattern::Value(Value::Range(RangeValue(begin, end))) => match answer {
+            Answer::Integer(number) => (*begin..=*end).contains(number),
+            Answer::Decimal(number) => (*begin as f32..=*end as f32).contains(number),
+            Answer::Text(_) => false,
+        },
+
+        MatchPattern::Value(Value::Number(number)) => match (number, answer) {
+            (NumberValue::Integer(number), Answer::Integer(answer)) => number == answer,
+            (NumberValue::Integer(number), Answer::Decimal(answer)) => *number as f32 == *answer,
+            (NumberValue::Decimal(number), Answer::Integer(answer)) => *number == *answer as f32,
+            (NumberValue::Decimal(number), Answer::Decimal(answer)) => number == answer,
+            (_, Answer::Text(_)) => false,
+        },
+    }
+}
+
+fn invalid(message: String, position: &Position) -> LoadError {
+    enviroment_error(Error::Evaluation { message, position: position.clone() })
+}
+
+fn enviroment_error(err: Error) -> LoadError {
+    LoadError::Template(vec![(ENVIROMENT_FILE_NAME, err)])
+}
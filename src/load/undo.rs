@@ -0,0 +1,78 @@
+use crate::load::hash;
+use crate::load::journal::{self, Record, Recorded};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The files written by `recorded` whose content changed since, files that were deleted are not included.
+pub fn modified(target: &Path, recorded: &Recorded) -> Vec<PathBuf> {
+    let mut modified = Vec::new();
+
+    for record in &recorded.records {
+        let (Record::File { path, hash } | Record::Overwrite { path, hash }) = record else {
+            continue;
+        };
+
+        let current = target.join(path);
+
+        if (current.exists() || current.is_symlink()) && digest(&current).ok().as_ref() != Some(hash) {
+            modified.push(path.clone());
+        }
+    }
+
+    return modified;
+}
+
+// The digest in the form the journal recorded it, a symlink is hashed by where it points like `Action::Symlink` does
+fn digest(path: &Path) -> io::Result<String> {
+    if let Ok(link) = fs::read_link(path) {
+        return Ok(hash::digest(link.as_os_str().as_encoded_bytes()));
+    }
+
+    return hash::digest_file(path);
+}
+
+/// Reverts the load in `recorded` and forgets it, returning a line for each restored path.
+pub fn undo(target: &Path, recorded: Recorded) -> Vec<String> {
+    let report = journal::revert(target, &recorded.directory, &recorded.records);
+
+    journal::discard(target, &recorded.directory);
+
+    return report;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::modified;
+    use crate::load::hash;
+    use crate::load::journal::{Record, Recorded};
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[cfg(unix)]
+    #[test]
+    fn kept_symlink_is_not_modified() {
+        let target = std::env::temp_dir().join(format!("plat-undo-{}-symlink", std::process::id()));
+        let _ = fs::remove_dir_all(&target);
+
+        fs::create_dir_all(&target).expect("Create target directory");
+        fs::write(target.join("a.txt"), "a").expect("Write test file");
+        std::os::unix::fs::symlink("a.txt", target.join("link")).expect("Create symlink");
+
+        let record = |path: &str, hash: String| Record::File { path: PathBuf::from(path), hash };
+
+        let recorded = Recorded {
+            directory: PathBuf::new(),
+            template: String::from("t"),
+            started: String::new(),
+            records: vec![record("a.txt", hash::digest(b"a")), record("link", hash::digest(b"a.txt"))],
+        };
+
+        assert!(modified(&target, &recorded).is_empty());
+
+        fs::remove_file(target.join("link")).expect("Remove symlink");
+        std::os::unix::fs::symlink("elsewhere", target.join("link")).expect("Create symlink");
+
+        assert_eq!(modified(&target, &recorded), [PathBuf::from("link")]);
+    }
+}
@@ -0,0 +1,401 @@
+use crate::load::conflict::free_path;
+use crate::load::error::LoadError;
+use crate::load::hash;
+use crate::load::journal::Journal;
+use crate::load::lockfile::{self, Lockfile};
+use crate::load::copy::{self, CopyOptions};
+use crate::load::plan::{self, Action, Plan};
+use crate::load::{check_destination, interrupted, record_generation, write_step, Generated};
+use crate::task::answers::Answers;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// What an update did to a single file.
+pub enum Outcome {
+    Created,
+    Updated,
+    Merged,
+    Conflict,
+    ConflictBeside(PathBuf),
+    KeptChanges,
+    KeptDeleted,
+    Removed,
+    KeptRemoved,
+}
+
+impl Outcome {
+    pub fn is_conflict(&self) -> bool {
+        matches!(self, Outcome::Conflict | Outcome::ConflictBeside(_))
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Created => write!(f, "created"),
+            Outcome::Updated => write!(f, "updated"),
+            Outcome::Merged => write!(f, "merged with local changes"),
+            Outcome::Conflict => write!(f, "conflict, resolve the <<<<<<< markers"),
+            Outcome::ConflictBeside(path) => write!(f, "conflict, the new version was written to {}", path.display()),
+            Outcome::KeptChanges => write!(f, "kept, only changed locally"),
+            Outcome::KeptDeleted => write!(f, "kept deleted"),
+            Outcome::Removed => write!(f, "removed, no longer part of the template"),
+            Outcome::KeptRemoved => write!(f, "kept, no longer part of the template but changed locally"),
+        }
+    }
+}
+
+/// Re-applies the plan of a template to a `target` it was loaded into before, as recorded in `previous`.
+/// Files the user did not touch are replaced, files changed on both sides are merged against the snapshot taken
+/// when they were last generated, with conflict markers where the changes overlap. Returns the outcome for every
+/// file that changed, unchanged files are left out.
+pub fn update(plan: &Plan, template_hash: String, target: &Path, answers: &Answers, previous: &Lockfile, copy: &CopyOptions, journal: &mut Journal) -> Result<Vec<(String, Outcome)>, LoadError> {
+    let mut outcomes = Vec::new();
+    let mut generated = Generated { files: BTreeMap::new(), renamed: BTreeMap::new() };
+
+    for step in &plan.steps {
+        if interrupted() {
+            return Err(LoadError::Interrupted);
+        }
+
+        if let Action::CreateDirectory = step.action {
+            let path = target.join(&step.path);
+
+            check_destination(target, &path, &step.action)?;
+            journal.create_directories(&path)?;
+            continue;
+        }
+
+        let planned = lockfile::file_key(&step.path);
+
+        // A file renamed beside one of the user's own is updated where it was written, the user's file is left alone
+        let key = match previous.renamed.get(&planned) {
+            Some(renamed) => {
+                generated.renamed.insert(planned, renamed.clone());
+                renamed.clone()
+            }
+
+            None => planned,
+        };
+
+        let path = target.join(&key);
+
+        check_destination(target, &path, &step.action)?;
+        generated.files.insert(key.clone(), &step.action);
+
+        if let Some(outcome) = update_file(&key, &path, &step.action, target, previous, copy, journal)? {
+            outcomes.push((key, outcome));
+        }
+    }
+
+    for (key, recorded) in &previous.files {
+        let path = target.join(key);
+
+        if generated.files.contains_key(key) || !path.is_file() {
+            continue;
+        }
+
+        let current = hash::digest_file(&path)
+            .map_err(|source| LoadError::Io { action: "read existing file", path: path.clone(), source })?;
+
+        if &current == recorded {
+            journal.remove(&path)?;
+            outcomes.push((key.clone(), Outcome::Removed));
+        } else {
+            outcomes.push((key.clone(), Outcome::KeptRemoved));
+        }
+    }
+
+    record_generation(&previous.template, template_hash, answers, copy, &generated, target, journal)?;
+
+    return Ok(outcomes);
+}
+
+fn update_file(key: &str, path: &Path, action: &Action, target: &Path, previous: &Lockfile, copy: &CopyOptions, journal: &mut Journal) -> Result<Option<Outcome>, LoadError> {
+    let recorded = previous.files.get(key);
+
+    if !path.exists() && !path.is_symlink() {
+        if recorded.is_some() {
+            return Ok(Some(Outcome::KeptDeleted));
+        }
+
+        write_step(action, path, copy, journal)?;
+        return Ok(Some(Outcome::Created));
+    }
+
+    let incoming = action.read_content()
+        .map_err(|source| LoadError::Io { action: "read template file", path: action.source(), source })?;
+    let current = plan::read_existing(path, action)
+        .map_err(|source| LoadError::Io { action: "read existing file", path: path.to_path_buf(), source })?;
+
+    if current == incoming {
+        return Ok(None);
+    }
+
+    // A file rewritten in place has no snapshot, the task already worked from its current content
+    if let Action::Edit { .. } = action {
+        write_step(action, path, copy, journal)?;
+        return Ok(Some(Outcome::Updated));
+    }
+
+    // Untouched since the last load, the new version replaces it
+    if recorded == Some(&hash::digest(&current)) {
+        write_step(action, path, copy, journal)?;
+        return Ok(Some(Outcome::Updated));
+    }
+
+    // The template did not change this file, only the user did
+    if recorded == Some(&hash::digest(&incoming)) {
+        return Ok(Some(Outcome::KeptChanges));
+    }
+
+    // Without a snapshot every line counts as added on both sides, so the whole file ends up in conflict
+    let base_path = lockfile::base_path(target, key);
+    let base = if recorded.is_some() && base_path.is_file() {
+        fs::read(&base_path)
+            .map_err(|source| LoadError::Io { action: "read snapshot", path: base_path.clone(), source })?
+    } else {
+        Vec::new()
+    };
+
+    // Only text files can be merged, anything else gets the new version written next to it
+    let (Action::Copy(source) | Action::Generate { source, .. }, Ok(base), Ok(ours), Ok(theirs)) = (action, std::str::from_utf8(&base), std::str::from_utf8(&current), std::str::from_utf8(&incoming)) else {
+        let beside = free_path(path);
+        write_step(action, &beside, copy, journal)?;
+
+        return Ok(Some(Outcome::ConflictBeside(beside.strip_prefix(target).unwrap_or(&beside).to_path_buf())));
+    };
+
+    let (merged, outcome) = match diffy::merge(base, ours, theirs) {
+        Ok(merged) => (merged, Outcome::Merged),
+        Err(conflicted) => (conflicted, Outcome::Conflict),
+    };
+
+    journal.write(path, merged.as_bytes())?;
+
+    copy::copy_permissions(source, path)
+        .map_err(|source| LoadError::Io { action: "set permissions of", path: path.to_path_buf(), source })?;
+
+    return Ok(Some(outcome));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::update;
+    use crate::load::copy::CopyOptions;
+    use crate::load::hash;
+    use crate::load::journal::Journal;
+    use crate::load::lockfile::{self, Lockfile};
+    use crate::load::plan::{Action, Plan};
+    use crate::task::answers::Answers;
+    use std::collections::{BTreeMap, BTreeSet};
+    use std::fs;
+    use std::path::PathBuf;
+
+    // A template and a target it was loaded into, `base` is what the last load generated
+    struct Fixture {
+        template: PathBuf,
+        target: PathBuf,
+        base: BTreeMap<String, Vec<u8>>,
+        renamed: BTreeMap<String, String>,
+    }
+
+    impl Fixture {
+        fn new(name: &str) -> Fixture {
+            let root = std::env::temp_dir().join(format!("plat-update-{}-{}", std::process::id(), name));
+            let _ = fs::remove_dir_all(&root);
+
+            let fixture = Fixture { template: root.join("template"), target: root.join("target"), base: BTreeMap::new(), renamed: BTreeMap::new() };
+
+            fs::create_dir_all(&fixture.template).expect("Create template directory");
+            fs::create_dir_all(&fixture.target).expect("Create target directory");
+
+            return fixture;
+        }
+
+        fn template(&self, key: &str, content: &[u8]) {
+            fs::write(self.template.join(key), content).expect("Write template file");
+        }
+
+        fn current(&self, key: &str, content: &[u8]) {
+            fs::write(self.target.join(key), content).expect("Write target file");
+        }
+
+        fn base(&mut self, key: &str, content: &[u8]) {
+            self.base.insert(key.to_string(), content.to_vec());
+        }
+
+        fn read(&self, key: &str) -> String {
+            fs::read_to_string(self.target.join(key)).expect("Read target file")
+        }
+
+        // Updates the target to the template, returning each outcome as it is printed
+        fn update(&self) -> Vec<(String, String)> {
+            let mut files = BTreeMap::new();
+
+            for (key, content) in &self.base {
+                let base = lockfile::base_path(&self.target, key);
+
+                fs::create_dir_all(base.parent().unwrap()).expect("Create snapshot directory");
+                fs::write(&base, content).expect("Write snapshot");
+                files.insert(key.clone(), hash::digest(content));
+            }
+
+            let previous = Lockfile {
+                template: String::from("t"),
+                template_hash: String::new(),
+                plat_version: String::new(),
+                generated: String::new(),
+                answers: BTreeMap::new(),
+                files,
+                edited: BTreeSet::new(),
+                copy: CopyOptions::default(),
+                renamed: self.renamed.clone(),
+            };
+
+            let mut entries = fs::read_dir(&self.template).unwrap().map(|entry| entry.unwrap().path()).collect::<Vec<_>>();
+            entries.sort();
+
+            let mut plan = Plan::new();
+
+            for path in entries {
+                plan.push(PathBuf::from(path.file_name().unwrap()), Action::Copy(path), None);
+            }
+
+            let mut journal = Journal::begin(&self.target, "t").ok().expect("Begin journal");
+            let outcomes = update(&plan, String::new(), &self.target, &Answers::new(), &previous, &CopyOptions::default(), &mut journal)
+                .ok()
+                .expect("Update target");
+
+            return outcomes.into_iter().map(|(key, outcome)| (key, outcome.to_string())).collect();
+        }
+    }
+
+    fn outcome(key: &str, outcome: &str) -> (String, String) {
+        (key.to_string(), outcome.to_string())
+    }
+
+    #[test]
+    fn renamed_file_is_updated_where_it_was_written() {
+        let mut fixture = Fixture::new("renamed");
+        fixture.template("f.txt", b"a\nc\n");
+        fixture.current("f.txt", b"mine\n");
+        fixture.current("f.txt.plat-new", b"a\nb\n");
+        fixture.base("f.txt.plat-new", b"a\nb\n");
+        fixture.renamed.insert(String::from("f.txt"), String::from("f.txt.plat-new"));
+
+        assert_eq!(fixture.update(), [outcome("f.txt.plat-new", "updated")]);
+        assert_eq!(fixture.read("f.txt"), "mine\n");
+        assert_eq!(fixture.read("f.txt.plat-new"), "a\nc\n");
+
+        let lockfile = Lockfile::read(&fixture.target).ok().flatten().expect("Read lockfile");
+
+        assert_eq!(lockfile.renamed.get("f.txt").map(String::as_str), Some("f.txt.plat-new"));
+        assert!(!lockfile.files.contains_key("f.txt"));
+    }
+
+    #[test]
+    fn renamed_file_is_removed_with_the_template_file() {
+        let mut fixture = Fixture::new("renamed-removed");
+        fixture.template("g.txt", b"g\n");
+        fixture.current("f.txt", b"mine\n");
+        fixture.current("f.txt.plat-new", b"a\nb\n");
+        fixture.base("f.txt.plat-new", b"a\nb\n");
+        fixture.renamed.insert(String::from("f.txt"), String::from("f.txt.plat-new"));
+
+        assert_eq!(fixture.update(), [outcome("g.txt", "created"), outcome("f.txt.plat-new", "removed, no longer part of the template")]);
+        assert_eq!(fixture.read("f.txt"), "mine\n");
+    }
+
+    #[test]
+    fn untouched_file_is_replaced() {
+        let mut fixture = Fixture::new("untouched");
+        fixture.template("f.txt", b"a\nc\n");
+        fixture.current("f.txt", b"a\nb\n");
+        fixture.base("f.txt", b"a\nb\n");
+
+        assert_eq!(fixture.update(), [outcome("f.txt", "updated")]);
+        assert_eq!(fixture.read("f.txt"), "a\nc\n");
+    }
+
+    #[test]
+    fn local_only_change_is_kept() {
+        let mut fixture = Fixture::new("local-only");
+        fixture.template("f.txt", b"a\nb\n");
+        fixture.current("f.txt", b"a\nmine\n");
+        fixture.base("f.txt", b"a\nb\n");
+
+        assert_eq!(fixture.update(), [outcome("f.txt", "kept, only changed locally")]);
+        assert_eq!(fixture.read("f.txt"), "a\nmine\n");
+    }
+
+    #[test]
+    fn separate_changes_are_merged() {
+        let mut fixture = Fixture::new("merged");
+        fixture.template("f.txt", b"one\ntwo\nthree\nfour\nfive\nsix\nseven\n");
+        fixture.current("f.txt", b"ONE\ntwo\nthree\nfour\nfive\nsix\nseven\n");
+        fixture.base("f.txt", b"one\ntwo\nthree\nfour\nfive\nsix\nSEVEN\n");
+
+        assert_eq!(fixture.update(), [outcome("f.txt", "merged with local changes")]);
+        assert_eq!(fixture.read("f.txt"), "ONE\ntwo\nthree\nfour\nfive\nsix\nseven\n");
+    }
+
+    #[test]
+    fn overlapping_changes_get_conflict_markers() {
+        let mut fixture = Fixture::new("conflict");
+        fixture.template("f.txt", b"a\ntheirs\nc\n");
+        fixture.current("f.txt", b"a\nours\nc\n");
+        fixture.base("f.txt", b"a\nb\nc\n");
+
+        assert_eq!(fixture.update(), [outcome("f.txt", "conflict, resolve the <<<<<<< markers")]);
+
+        let merged = fixture.read("f.txt");
+
+        assert!(merged.contains("<<<<<<< ours\nours\n"));
+        assert!(merged.contains("=======\ntheirs\n>>>>>>> theirs"));
+    }
+
+    #[test]
+    fn binary_file_is_written_beside() {
+        let mut fixture = Fixture::new("binary");
+        fixture.template("f.bin", &[0, 159, 2]);
+        fixture.current("f.bin", &[0, 159, 1]);
+        fixture.base("f.bin", &[0, 159, 0]);
+
+        assert_eq!(fixture.update(), [outcome("f.bin", "conflict, the new version was written to f.bin.plat-new")]);
+        assert_eq!(fs::read(fixture.target.join("f.bin")).unwrap(), [0, 159, 1]);
+        assert_eq!(fs::read(fixture.target.join("f.bin.plat-new")).unwrap(), [0, 159, 2]);
+    }
+
+    #[test]
+    fn new_file_is_created_and_deleted_file_stays_deleted() {
+        let mut fixture = Fixture::new("created");
+        fixture.template("deleted.txt", b"b\n");
+        fixture.template("new.txt", b"a\n");
+        fixture.base("deleted.txt", b"a\n");
+
+        assert_eq!(fixture.update(), [outcome("deleted.txt", "kept deleted"), outcome("new.txt", "created")]);
+        assert!(!fixture.target.join("deleted.txt").exists());
+    }
+
+    #[test]
+    fn file_dropped_by_template_is_removed_unless_changed() {
+        let mut fixture = Fixture::new("removed");
+        fixture.template("kept.txt", b"k\n");
+        fixture.current("kept.txt", b"k\n");
+        fixture.current("changed.txt", b"mine\n");
+        fixture.current("dropped.txt", b"a\n");
+        fixture.base("kept.txt", b"k\n");
+        fixture.base("changed.txt", b"a\n");
+        fixture.base("dropped.txt", b"a\n");
+
+        assert_eq!(fixture.update(), [
+            outcome("changed.txt", "kept, no longer part of the template but changed locally"),
+            outcome("dropped.txt", "removed, no longer part of the template"),
+        ]);
+        assert!(!fixture.target.join("dropped.txt").exists());
+        assert_eq!(fixture.read("changed.txt"), "mine\n");
+    }
+}
@@ -1,136 +1,28 @@
 #![allow(unused)]
 #![allow(clippy::needless_return)]
 
+mod commands;
+mod load;
+mod prompt;
+mod registry;
 mod task;
+mod timestamp;
 
-use clap::{Arg, Command};
-use indicatif::ProgressBar;
-use std::any::Any;
-use std::collections::HashMap;
-use std::env;
-use std::fs::{File, OpenOptions};
-use std::io::{BufRead, BufReader, Read, Seek, Write};
+use clap::{Arg, ArgAction, Command};
 use std::path::PathBuf;
-use peekmore::{PeekMore, PeekMoreIterator};
-use task::layers::parsers::enviroment::{parse_enviroment, Statement};
-use crate::task::layers::fragmentize::fragmentize;
-use crate::task::layers::parsers::commands::parse_commands;
-use crate::task::layers::tokenize::tokenize;
-use crate::task::nodes::collection::NodeCollection;
-
-fn open_data_file() -> File {
-    let path = env::current_exe()
-        .expect("Get current executable path")
-        .parent()
-        .expect("Get parent directory of executable")
-        .join(".platdata");
-
-    return OpenOptions::new()
-        .create(true)
-        .truncate(false)
-        .read(true)
-        .write(true)
-        .open(path)
-        .expect("Open config file");
-}
-
-fn read_data_file() -> HashMap<String, String> {
-    let mut map: HashMap<String, String> = HashMap::new();
-
-    let mut data_file = open_data_file();
-    let reader = BufReader::new(&data_file);
-
-    for line in reader.lines() {
-        let line = line.expect("Read line from file");
-
-        if let Some(pos) = line.find('|') {
-            let name = &line[..pos];
-            let path = &line[pos + 1..];
-
-            map.insert(name.to_string(), path.to_string());
-        }
-    }
-
-    return map;
-}
-
-fn write_data_file(data: &HashMap<String, String>) {
-    let mut data_file = open_data_file();
-
-    data_file.set_len(0).expect("Truncate file");
-    data_file
-        .seek(std::io::SeekFrom::Start(0))
-        .expect("Seek to start of file");
-
-    for (name, path) in data {
-        writeln!(data_file, "{}|{}", name, path).expect("Write to file");
-    }
-}
-
-fn load(origin: PathBuf, target: PathBuf, progress_bar: ProgressBar) {
-    let task_file_path = origin.join("task.plat");
-
-    if task_file_path.exists() {
-        let content = std::fs::read_to_string(&task_file_path).expect("Read task file");
-
-        return;
-    }
-
-    let mut options = fs_extra::dir::CopyOptions::new()
-        .copy_inside(true)
-        .overwrite(true);
-
-    fs_extra::dir::copy(&origin, &target, &options).expect("Copy files");
-}
 
 fn main() {
-    let file = File::open("src/.plat").expect("Read test file");
-    let mut reader = BufReader::new(&file);
-
-    let mut data = String::new();
-    reader.read_to_string(&mut data).unwrap();
-
-    let fragments = fragmentize(&data);
-
-    // for fragment in fragments {
-    //     println!("{:?}", fragment);
-    // }
-    // return;
-
-    let tokens = match tokenize(fragments) {
-        NodeCollection::Ok(nodes) => nodes,
-        NodeCollection::Failed(errors) => {
-            for error in errors {
-                println!("{}", error);
-            }
-            return;
-        }
-    };
-    
-    // for token in tokens {
-    //     println!("{:?}", token);
-    // }
-    // return;
-
-    let compounds = match parse_commands(tokens) {
-        NodeCollection::Ok(compounds) => compounds,
-        NodeCollection::Failed(errors) => {
-            for error in errors {
-                println!("{}", error);
-            }
-            return;
-        }
-    };
-
-    for compound in compounds {
-        println!("{:?}", compound);
-    }
-
-
-    return;
-
     let app = Command::new("plat")
         .version("1.0")
+        .arg(
+            Arg::new("yes")
+                .long("yes")
+                .short('y')
+                .visible_alias("non-interactive")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help("Answer confirmations with yes and never wait for input, for scripts and CI"),
+        )
         .subcommand(
             Command::new("load")
                 .alias("l")
@@ -138,127 +30,171 @@ fn main() {
                 .arg(
                     Arg::new("name")
                         .required(true)
-                        .help("The name of the template to load")
+                        .help("The name of the template to load, or an unambiguous suffix of it")
                         .index(1),
+                )
+                .arg(
+                    Arg::new("target")
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .help("The directory to load the template into, defaults to the current directory")
+                        .index(2),
+                )
+                .arg(Arg::new("force").long("force").short('f').action(ArgAction::SetTrue).help("Load into a non-empty directory without a warning"))
+                .arg(
+                    Arg::new("on-conflict")
+                        .long("on-conflict")
+                        .value_parser(["skip", "overwrite", "prompt", "rename", "fail"])
+                        .default_value("prompt")
+                        .help("What to do with files that already exist in the target"),
+                )
+                .arg(
+                    Arg::new("symlinks")
+                        .long("symlinks")
+                        .value_parser(["keep", "follow", "reject"])
+                        .default_value("keep")
+                        .help("Whether symlinks in the template are recreated, copied as the files they point to, or fail the load"),
+                )
+                .arg(Arg::new("preserve-mtime").long("preserve-mtime").action(ArgAction::SetTrue).help("Give copied files the modification time of the template files"))
+                .arg(
+                    Arg::new("answers")
+                        .long("answers")
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .help("A toml file with answers to the template's questions, those are not asked"),
+                )
+                .arg(
+                    Arg::new("dry-run")
+                        .long("dry-run")
+                        .short('n')
+                        .action(ArgAction::SetTrue)
+                        .help("Print what the template would create, overwrite or skip without changing anything"),
+                ),
+        )
+        .subcommand(
+            Command::new("undo")
+                .about("Reverts the most recent load into a directory")
+                .arg(
+                    Arg::new("target")
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .help("The directory the template was loaded into, defaults to the current directory")
+                        .index(1),
+                )
+                .arg(Arg::new("force").long("force").short('f').action(ArgAction::SetTrue).help("Undo even if loaded files were changed since")),
+        )
+        .subcommand(
+            Command::new("update")
+                .about("Merges the changes of a template into a directory it was loaded into")
+                .arg(
+                    Arg::new("target")
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .help("The directory the template was loaded into, defaults to the current directory")
+                        .index(1),
+                ),
+        )
+        .subcommand(
+            Command::new("diff")
+                .about("Shows how a directory differs from the output of a template")
+                .arg(Arg::new("name").required(true).help("The name of the template, or an unambiguous suffix of it").index(1))
+                .arg(
+                    Arg::new("target")
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .help("The directory to compare, defaults to the current directory")
+                        .index(2),
+                )
+                .arg(
+                    Arg::new("answers")
+                        .long("answers")
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .help("A toml file with the answers to render the template with, defaults to the answers recorded in .plat-lock"),
                 ),
         )
         .subcommand(
             Command::new("link")
                 .arg(Arg::new("name").help("The name of the template").index(1))
+                .arg(Arg::new("description").long("description").short('d').help("A short description of the template"))
+                .arg(Arg::new("tag").long("tag").short('t').action(ArgAction::Append).help("A tag to categorize the template, can be repeated"))
+                .arg(Arg::new("author").long("author").help("The author of the template"))
+                .arg(Arg::new("version").long("version").help("The version of the template"))
                 .about("Links the current directory as a template"),
         )
-        .subcommand(Command::new("unlink").about("Unlinks the current directory as a template"))
+        .subcommand(
+            Command::new("unlink")
+                .arg(Arg::new("name").help("The name of the template, defaults to the one linked at the current directory").index(1))
+                .about("Unlinks a template"),
+        )
+        .subcommand(
+            Command::new("rename")
+                .about("Renames a template")
+                .arg(Arg::new("name").required(true).help("The current name of the template").index(1))
+                .arg(Arg::new("new-name").required(true).help("The new name of the template").index(2)),
+        )
+        .subcommand(
+            Command::new("relink")
+                .about("Points a template to a different directory")
+                .arg(Arg::new("name").required(true).help("The name of the template").index(1))
+                .arg(
+                    Arg::new("path")
+                        .required(true)
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .help("The new directory of the template")
+                        .index(2),
+                ),
+        )
         .subcommand(
             Command::new("list")
                 .alias("ls")
-                .about("Lists all linked templates"),
-        );
+                .about("Lists all linked templates")
+                .arg(Arg::new("pattern").help("Only list templates in this namespace, or whose name matches this glob").index(1))
+                .arg(Arg::new("tag").long("tag").short('t').action(ArgAction::Append).help("Only list templates with this tag, can be repeated"))
+                .arg(Arg::new("long").long("long").short('l').action(ArgAction::SetTrue).help("Show whether each template directory exists and what it contains"))
+                .arg(Arg::new("json").long("json").action(ArgAction::SetTrue).conflicts_with_all(["long", "format"]).help("Print the templates as JSON"))
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .conflicts_with("long")
+                        .help("Print each template with a format string, e.g. '{name}\\t{path}'. Available fields: name, path, description, tags, author, version, created, last_used, exists, has_task, has_env"),
+                ),
+        )
+        .subcommand(
+            Command::new("info")
+                .about("Shows the details of a template")
+                .arg(
+                    Arg::new("name")
+                        .required(true)
+                        .help("The name of the template")
+                        .index(1),
+                ),
+        )
+        .subcommand(
+            Command::new("doctor")
+                .about("Checks the registry and every linked template for problems")
+                .arg(Arg::new("fix").long("fix").action(ArgAction::SetTrue).help("Unlink templates whose directory is missing without asking")),
+        )
+        .subcommand(Command::new("registry").about("Prints the location of the template registry"));
 
     let matches = app.clone().get_matches();
 
-    let current_dir = env::current_dir().expect("Get current directory");
-
-    match matches.subcommand() {
-        Some(("link", submatches)) => {
-            let mut data = read_data_file();
-
-            let name = submatches.get_one::<String>("name").map_or_else(
-                || {
-                    return dialoguer::Input::new()
-                        .with_prompt("Enter a name for the template")
-                        .interact()
-                        .expect("Prompt for template name");
-                },
-                |name| name.trim().to_string(),
-            );
-
-            let path = current_dir
-                .to_str()
-                .expect("Convert current directory to string");
-
-            if data.contains_key(&name) {
-                println!(
-                    "A template with the name '{}' already exists, please choose a different name",
-                    name
-                );
-                return;
-            }
-
-            if data.values().any(|v| v == path) {
-                println!("The template at '{}' is already linked.", path);
-                return;
-            }
-
-            data.insert(name.to_string(), path.to_string());
-
-            write_data_file(&data);
-
-            println!("Template {} is now linked.", name);
-        }
-
-        Some(("unlink", _)) => {
-            let mut data = read_data_file();
-
-            let path = current_dir
-                .to_str()
-                .expect("Convert current directory to string");
-
-            data.retain(|_, v| v != path);
-
-            write_data_file(&data);
-
-            println!("Template {} is now unlinked.", path);
-        }
-
-        Some(("load", load_matches)) => {
-            let name = load_matches
-                .get_one::<String>("name")
-                .expect("Get name argument");
-            let data = read_data_file();
-
-            if let Some(path) = data.get(name) {
-                let confirmed = dialoguer::Confirm::new()
-                    .with_prompt(format!(
-                        "Do you want to load the template '{}' into the current directory?",
-                        name
-                    ))
-                    .interact()
-                    .expect("Prompt confirm message before loading");
-
-                if !confirmed {
-                    return;
-                }
-
-                println!("Loading template from {}", path);
-
-                let origin_path = PathBuf::from(path);
-                let progress_bar = ProgressBar::new(100);
-
-                load(origin_path, current_dir, progress_bar);
-
-                println!("Finished loading template");
-            } else {
-                println!("Template '{}' was not found, try checking the linked templates with 'plat list'", name);
-            }
-        }
-
-        Some(("list", _)) => {
-            let data = read_data_file();
-
-            if data.is_empty() {
-                println!("No templates linked.");
-            } else {
-                println!("Linked templates:");
-
-                for (name, path) in data {
-                    println!("{} -> {}", name, path);
-                }
-            }
-        }
+    let result = match matches.subcommand() {
+        Some(("link", submatches)) => commands::link::run(submatches),
+        Some(("unlink", submatches)) => commands::unlink::run(submatches),
+        Some(("rename", submatches)) => commands::rename::run(submatches),
+        Some(("relink", submatches)) => commands::relink::run(submatches),
+        Some(("load", submatches)) => commands::load::run(submatches),
+        Some(("undo", submatches)) => commands::undo::run(submatches),
+        Some(("update", submatches)) => commands::update::run(submatches),
+        Some(("diff", submatches)) => commands::diff::run(submatches),
+        Some(("list", submatches)) => commands::list::run(submatches),
+        Some(("info", submatches)) => commands::info::run(submatches),
+        Some(("doctor", submatches)) => commands::doctor::run(submatches),
+        Some(("registry", submatches)) => commands::registry::run(submatches),
 
         _ => {
             app.clone().print_help().expect("Print help");
             std::process::exit(0);
         }
+    };
+
+    if let Err(err) = result {
+        eprintln!("{}", err);
+        std::process::exit(1);
     }
 }
@@ -0,0 +1,128 @@
+use clap::ArgMatches;
+use std::fmt::{Display, Formatter};
+use std::io::{self, IsTerminal};
+
+pub enum PromptError {
+    NonInteractive {
+        prompt: String,
+        hint: &'static str,
+    },
+    Failed(dialoguer::Error),
+}
+
+impl Display for PromptError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PromptError::NonInteractive { prompt, hint } => {
+                write!(f, "Can not ask \"{}\" without an interactive terminal, {}", prompt, hint)
+            }
+
+            PromptError::Failed(err) => {
+                write!(f, "Failed to prompt: {}", err)
+            }
+        }
+    }
+}
+
+/// Asks the user questions, or answers them on their behalf when running with `--yes` or without a terminal.
+pub struct Prompter {
+    assume_yes: bool,
+    interactive: bool,
+}
+
+impl Prompter {
+    pub fn new(matches: &ArgMatches) -> Self {
+        Prompter {
+            assume_yes: matches.get_flag("yes"),
+            interactive: io::stdin().is_terminal(),
+        }
+    }
+
+    pub fn is_interactive(&self) -> bool {
+        self.interactive && !self.assume_yes
+    }
+
+    /// Asks a yes/no question, `--yes` accepts it without asking.
+    pub fn confirm(&self, prompt: &str) -> Result<bool, PromptError> {
+        if self.assume_yes {
+            return Ok(true);
+        }
+
+        self.require_terminal(prompt, "pass --yes to confirm automatically")?;
+
+        return dialoguer::Confirm::new()
+            .with_prompt(prompt)
+            .interact()
+            .map_err(PromptError::Failed);
+    }
+
+    /// Asks for a line of text, there is no automatic answer so `hint` should tell how to pass it instead.
+    pub fn input(&self, prompt: &str, hint: &'static str, validate: impl Fn(&String) -> Result<(), String>) -> Result<String, PromptError> {
+        if !self.is_interactive() {
+            return Err(PromptError::NonInteractive { prompt: prompt.to_string(), hint });
+        }
+
+        return dialoguer::Input::new()
+            .with_prompt(prompt)
+            .validate_with(|input: &String| validate(input))
+            .interact()
+            .map_err(PromptError::Failed);
+    }
+
+    /// Asks for a line of text without showing what is typed, there is no automatic answer so `hint` should tell how to pass it instead.
+    pub fn password(&self, prompt: &str, hint: &'static str, validate: impl Fn(&String) -> Result<(), String>) -> Result<String, PromptError> {
+        if !self.is_interactive() {
+            return Err(PromptError::NonInteractive { prompt: prompt.to_string(), hint });
+        }
+
+        return dialoguer::Password::new()
+            .with_prompt(prompt)
+            .validate_with(|input: &String| validate(input))
+            .interact()
+            .map_err(PromptError::Failed);
+    }
+
+    /// Lets the user pick one item, there is no automatic answer so `hint` should tell how to avoid the question.
+    pub fn select(&self, prompt: &str, items: &[&str], hint: &'static str) -> Result<usize, PromptError> {
+        if !self.is_interactive() {
+            return Err(PromptError::NonInteractive { prompt: prompt.to_string(), hint });
+        }
+
+        return dialoguer::Select::new()
+            .with_prompt(prompt)
+            .items(items)
+            .default(0)
+            .interact()
+            .map_err(PromptError::Failed);
+    }
+
+    /// Lets the user pick any number of items, `--yes` accepts the defaults.
+    pub fn multi_select<T: ToString>(&self, prompt: &str, items: &[T], defaults: &[bool]) -> Result<Vec<usize>, PromptError> {
+        if self.assume_yes {
+            let selected = defaults.iter()
+                .enumerate()
+                .filter(|(_, selected)| **selected)
+                .map(|(index, _)| index)
+                .collect();
+
+            return Ok(selected);
+        }
+
+        self.require_terminal(prompt, "pass --yes to accept the default selection")?;
+
+        return dialoguer::MultiSelect::new()
+            .with_prompt(prompt)
+            .items(items)
+            .defaults(defaults)
+            .interact()
+            .map_err(PromptError::Failed);
+    }
+
+    fn require_terminal(&self, prompt: &str, hint: &'static str) -> Result<(), PromptError> {
+        if !self.interactive {
+            return Err(PromptError::NonInteractive { prompt: prompt.to_string(), hint });
+        }
+
+        return Ok(());
+    }
+}
@@ -0,0 +1,98 @@
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::PathBuf;
+
+pub enum RegistryError {
+    Malformed {
+        path: PathBuf,
+        message: String,
+    },
+    UnsupportedVersion {
+        path: PathBuf,
+        found: i64,
+    },
+    Manifest {
+        path: PathBuf,
+        message: String,
+    },
+    Io {
+        action: &'static str,
+        path: PathBuf,
+        source: io::Error,
+    },
+    NoLocation,
+    NotFound(String),
+    NameTaken(String),
+    InvalidName {
+        name: String,
+        reason: String,
+    },
+    Ambiguous {
+        query: String,
+        candidates: Vec<String>,
+    },
+    PathTaken {
+        path: PathBuf,
+        name: String,
+    },
+}
+
+impl Display for RegistryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RegistryError::Malformed { path, message } => {
+                write!(f, "Registry file '{}' is malformed: {}",
+                       path.display(),
+                       message
+                )
+            }
+
+            RegistryError::UnsupportedVersion { path, found } => {
+                write!(f, "Registry file '{}' has version {}, but this build of plat only understands versions up to {}. Please update plat.",
+                       path.display(),
+                       found,
+                       super::REGISTRY_VERSION
+                )
+            }
+
+            RegistryError::Manifest { path, message } => {
+                write!(f, "Template manifest '{}' is malformed: {}",
+                       path.display(),
+                       message
+                )
+            }
+
+            RegistryError::Io { action, path, source } => {
+                write!(f, "Failed to {} '{}': {}",
+                       action,
+                       path.display(),
+                       source
+                )
+            }
+
+            RegistryError::NoLocation => {
+                write!(f, "Could not resolve a home directory for the registry, set PLAT_HOME to choose a location")
+            }
+
+            RegistryError::NotFound(name) => {
+                write!(f, "Template '{}' was not found, try checking the linked templates with 'plat list'", name)
+            }
+
+            RegistryError::NameTaken(name) => {
+                write!(f, "A template with the name '{}' already exists, please choose a different name", name)
+            }
+
+            RegistryError::InvalidName { name, reason } => {
+                write!(f, "'{}' is not a valid template name: {}", name, reason)
+            }
+
+            RegistryError::Ambiguous { query, candidates } => {
+                write!(f, "'{}' matches multiple templates, use one of: {}", query, candidates.join(", "))
+            }
+
+            RegistryError::PathTaken { path, name } => {
+                write!(f, "The template at '{}' is already linked as '{}'.", path.display(), name)
+            }
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use crate::registry::error::RegistryError;
+use crate::registry::location::executable_directory;
+use crate::registry::lock::RegistryLock;
+use crate::registry::metadata::Metadata;
+use crate::registry::{Entry, Registry};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const LEGACY_DATA_FILE_NAME: &str = ".platdata";
+const LEGACY_REGISTRY_FILE_NAME: &str = ".platregistry";
+
+/// Moves data left next to the executable by older releases into the current registry location.
+/// Both the `name|path` data file and the first versioned registry are picked up, the old file is
+/// renamed with a `.bak` extension so nothing is lost if the conversion is wrong.
+pub fn migrate(lock: &RegistryLock) -> Result<Registry, RegistryError> {
+    let Some(directory) = executable_directory() else {
+        return Ok(Registry::new());
+    };
+
+    let registry_path = directory.join(LEGACY_REGISTRY_FILE_NAME);
+    let data_path = directory.join(LEGACY_DATA_FILE_NAME);
+
+    let (legacy_path, registry) = if registry_path.exists() {
+        let content = fs::read_to_string(&registry_path)
+            .map_err(|source| RegistryError::Io { action: "read legacy registry file", path: registry_path.clone(), source })?;
+        let registry = Registry::parse(registry_path.clone(), &content)?;
+
+        (registry_path, registry)
+    } else if data_path.exists() {
+        let content = fs::read_to_string(&data_path)
+            .map_err(|source| RegistryError::Io { action: "read legacy data file", path: data_path.clone(), source })?;
+        let registry = parse(&data_path, &content)?;
+
+        (data_path, registry)
+    } else {
+        return Ok(Registry::new());
+    };
+
+    registry.write(lock)?;
+
+    // Any command can trigger the migration, so the notes go to stderr and never into output meant for scripts
+    eprintln!("Migrated {} template(s) from '{}'", registry.templates.len(), legacy_path.display());
+
+    // The binary may live in a read-only location, the new registry takes precedence either way
+    let backup_path = legacy_path.with_extension("bak");
+
+    if fs::rename(&legacy_path, &backup_path).is_err() {
+        eprintln!("Could not rename '{}', it is no longer used and can be deleted", legacy_path.display());
+    }
+
+    return Ok(registry);
+}
+
+fn parse(path: &Path, content: &str) -> Result<Registry, RegistryError> {
+    let mut registry = Registry::new();
+
+    for (index, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let Some(pos) = line.find('|') else {
+            return Err(RegistryError::Malformed {
+                path: path.to_path_buf(),
+                message: format!("line {} is not in the 'name|path' format", index + 1),
+            });
+        };
+
+        let name = &line[..pos];
+        let template_path = &line[pos + 1..];
+
+        registry.templates.insert(name.to_string(), Entry {
+            path: PathBuf::from(template_path),
+            metadata: Metadata::default(),
+            created: None,
+            last_used: None,
+        });
+    }
+
+    return Ok(registry);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn reads_name_and_path() {
+        let registry = parse(Path::new(".platdata"), "web|/templates/web\n\ncli|/templates/cli\n").ok().unwrap();
+
+        assert_eq!(registry.templates.len(), 2);
+        assert_eq!(registry.templates["web"].path, PathBuf::from("/templates/web"));
+        assert_eq!(registry.templates["cli"].path, PathBuf::from("/templates/cli"));
+    }
+
+    #[test]
+    fn keeps_separator_inside_path() {
+        let registry = parse(Path::new(".platdata"), "web|/templates/a|b").ok().unwrap();
+
+        assert_eq!(registry.templates["web"].path, PathBuf::from("/templates/a|b"));
+    }
+
+    #[test]
+    fn rejects_line_without_separator() {
+        assert!(parse(Path::new(".platdata"), "web|/templates/web\nbroken").is_err());
+    }
+}
@@ -0,0 +1,46 @@
+use crate::registry::error::RegistryError;
+use std::env;
+use std::path::PathBuf;
+
+pub const REGISTRY_FILE_NAME: &str = "registry.toml";
+
+/// Resolves the directory holding the registry, in order of precedence:
+/// `$PLAT_HOME`, `$XDG_CONFIG_HOME/plat`, then `~/.config/plat`.
+pub fn registry_directory() -> Result<PathBuf, RegistryError> {
+    if let Some(home) = absolute_env("PLAT_HOME") {
+        return Ok(home);
+    }
+
+    if let Some(config) = absolute_env("XDG_CONFIG_HOME") {
+        return Ok(config.join("plat"));
+    }
+
+    let home = absolute_env("HOME")
+        .or_else(|| absolute_env("USERPROFILE"))
+        .ok_or(RegistryError::NoLocation)?;
+
+    return Ok(home.join(".config").join("plat"));
+}
+
+pub fn registry_path() -> Result<PathBuf, RegistryError> {
+    Ok(registry_directory()?.join(REGISTRY_FILE_NAME))
+}
+
+/// The directory older releases stored their data in.
+pub fn executable_directory() -> Option<PathBuf> {
+    let exe = env::current_exe().ok()?;
+
+    return exe.parent().map(|parent| parent.to_path_buf());
+}
+
+// Empty and relative values are ignored, as the XDG base directory spec requires.
+fn absolute_env(key: &str) -> Option<PathBuf> {
+    let value = env::var_os(key)?;
+    let path = PathBuf::from(value);
+
+    if path.is_absolute() {
+        Some(path)
+    } else {
+        None
+    }
+}
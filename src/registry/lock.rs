@@ -0,0 +1,45 @@
+use crate::registry::error::RegistryError;
+use crate::registry::location::registry_directory;
+use std::fs::{self, File, OpenOptions, TryLockError};
+
+const LOCK_FILE_NAME: &str = "registry.lock";
+
+/// Advisory lock over the registry directory, released when dropped.
+/// A separate lock file is used because the registry itself is replaced on every write.
+pub struct RegistryLock {
+    _file: File,
+}
+
+impl RegistryLock {
+    pub fn acquire() -> Result<RegistryLock, RegistryError> {
+        let directory = registry_directory()?;
+        let path = directory.join(LOCK_FILE_NAME);
+
+        fs::create_dir_all(&directory)
+            .map_err(|source| RegistryError::Io { action: "create registry directory", path: directory.clone(), source })?;
+
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .map_err(|source| RegistryError::Io { action: "open lock file", path: path.clone(), source })?;
+
+        match file.try_lock() {
+            Ok(()) => {}
+
+            Err(TryLockError::WouldBlock) => {
+                eprintln!("Waiting for another plat process to release the registry...");
+
+                file.lock()
+                    .map_err(|source| RegistryError::Io { action: "lock", path: path.clone(), source })?;
+            }
+
+            Err(TryLockError::Error(source)) => {
+                return Err(RegistryError::Io { action: "lock", path, source });
+            }
+        }
+
+        return Ok(RegistryLock { _file: file });
+    }
+}
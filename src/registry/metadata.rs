@@ -0,0 +1,53 @@
+use crate::registry::error::RegistryError;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+pub const MANIFEST_FILE_NAME: &str = "plat.toml";
+
+/// Descriptive information about a template, read from the `plat.toml` manifest in the
+/// template root and/or given on the command line when linking.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Metadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+impl Metadata {
+    /// Reads the manifest of the template at `directory`, a missing manifest yields empty metadata.
+    pub fn read_manifest(directory: &Path) -> Result<Metadata, RegistryError> {
+        let path = directory.join(MANIFEST_FILE_NAME);
+
+        if !path.exists() {
+            return Ok(Metadata::default());
+        }
+
+        let content = fs::read_to_string(&path)
+            .map_err(|source| RegistryError::Io { action: "read template manifest", path: path.clone(), source })?;
+
+        return toml::from_str(&content)
+            .map_err(|err| RegistryError::Manifest { path, message: err.message().to_string() });
+    }
+
+    /// Combines two sets of metadata, values set in `overrides` take precedence.
+    pub fn merge(self, overrides: Metadata) -> Metadata {
+        Metadata {
+            description: overrides.description.or(self.description),
+            tags: if overrides.tags.is_empty() { self.tags } else { overrides.tags },
+            author: overrides.author.or(self.author),
+            version: overrides.version.or(self.version),
+        }
+    }
+
+    /// The first line of the description, for single line listings.
+    pub fn summary(&self) -> Option<&str> {
+        self.description.as_deref()
+            .and_then(|description| description.lines().next())
+    }
+}
@@ -0,0 +1,206 @@
+pub mod error;
+pub mod location;
+pub mod lock;
+pub mod metadata;
+pub mod name;
+mod legacy;
+
+use crate::registry::error::RegistryError;
+use crate::registry::location::registry_path;
+use crate::registry::lock::RegistryLock;
+use crate::registry::metadata::Metadata;
+use crate::timestamp;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub const REGISTRY_VERSION: i64 = 1;
+
+#[derive(Serialize, Deserialize)]
+pub struct Registry {
+    pub version: i64,
+    #[serde(default)]
+    pub templates: BTreeMap<String, Entry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Entry {
+    pub path: PathBuf,
+    #[serde(flatten)]
+    pub metadata: Metadata,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_used: Option<String>,
+}
+
+impl Entry {
+    pub fn new(path: PathBuf, metadata: Metadata) -> Self {
+        Entry {
+            path,
+            metadata,
+            created: Some(timestamp::now()),
+            last_used: None,
+        }
+    }
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Registry {
+            version: REGISTRY_VERSION,
+            templates: BTreeMap::new(),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Result<&Entry, RegistryError> {
+        self.templates.get(name)
+            .ok_or_else(|| RegistryError::NotFound(name.to_string()))
+    }
+
+    /// Finds a template by its full name, or by an unambiguous suffix of namespace segments.
+    pub fn resolve(&self, query: &str) -> Result<(&String, &Entry), RegistryError> {
+        if let Some(found) = self.templates.get_key_value(query) {
+            return Ok(found);
+        }
+
+        let mut candidates = self.templates.iter()
+            .filter(|(name, _)| name::matches_suffix(name, query))
+            .collect::<Vec<_>>();
+
+        return match candidates.len() {
+            0 => Err(RegistryError::NotFound(query.to_string())),
+            1 => Ok(candidates.remove(0)),
+            _ => Err(RegistryError::Ambiguous {
+                query: query.to_string(),
+                candidates: candidates.into_iter().map(|(name, _)| name.clone()).collect(),
+            }),
+        };
+    }
+
+    /// Fails if `name` is not a valid template name or is already used by a template.
+    pub fn check_name(&self, name: &str) -> Result<(), RegistryError> {
+        name::validate(name)?;
+
+        if self.templates.contains_key(name) {
+            return Err(RegistryError::NameTaken(name.to_string()));
+        }
+
+        return Ok(());
+    }
+
+    /// Fails if `path` is already linked by a template other than `except`.
+    pub fn check_path(&self, path: &Path, except: Option<&str>) -> Result<(), RegistryError> {
+        let owner = self.templates.iter()
+            .find(|(name, entry)| entry.path == path && Some(name.as_str()) != except);
+
+        if let Some((name, _)) = owner {
+            return Err(RegistryError::PathTaken { path: path.to_path_buf(), name: name.clone() });
+        }
+
+        return Ok(());
+    }
+
+    /// Reads a snapshot of the registry, for commands that do not modify it.
+    pub fn read() -> Result<Registry, RegistryError> {
+        let lock = RegistryLock::acquire()?;
+
+        return Registry::read_locked(&lock);
+    }
+
+    /// Reads the registry while the caller holds the lock for a whole read-modify-write cycle.
+    pub fn read_locked(lock: &RegistryLock) -> Result<Registry, RegistryError> {
+        let path = registry_path()?;
+
+        if !path.exists() {
+            return legacy::migrate(lock);
+        }
+
+        let content = fs::read_to_string(&path)
+            .map_err(|source| RegistryError::Io { action: "read registry file", path: path.clone(), source })?;
+
+        return Registry::parse(path, &content);
+    }
+
+    /// Writes the registry into a temporary file and renames it over the old one,
+    /// so an interrupted write can never leave a truncated registry behind.
+    pub fn write(&self, _lock: &RegistryLock) -> Result<(), RegistryError> {
+        let path = registry_path()?;
+        let temp_path = path.with_extension("toml.tmp");
+
+        let content = toml::to_string_pretty(self)
+            .map_err(|err| RegistryError::Malformed { path: path.clone(), message: err.to_string() })?;
+
+        let mut file = File::create(&temp_path)
+            .map_err(|source| RegistryError::Io { action: "create temporary registry file", path: temp_path.clone(), source })?;
+
+        file.write_all(content.as_bytes())
+            .and_then(|_| file.sync_all())
+            .map_err(|source| RegistryError::Io { action: "write temporary registry file", path: temp_path.clone(), source })?;
+
+        fs::rename(&temp_path, &path)
+            .map_err(|source| RegistryError::Io { action: "replace registry file", path, source })?;
+
+        return Ok(());
+    }
+
+    fn parse(path: PathBuf, content: &str) -> Result<Registry, RegistryError> {
+        let table = content.parse::<toml::Table>()
+            .map_err(|err| RegistryError::Malformed { path: path.clone(), message: err.message().to_string() })?;
+
+        let version = match table.get("version") {
+            Some(toml::Value::Integer(version)) => *version,
+            Some(_) => return Err(RegistryError::Malformed { path, message: String::from("'version' must be an integer") }),
+            None => return Err(RegistryError::Malformed { path, message: String::from("missing 'version' field") }),
+        };
+
+        if version < 1 {
+            return Err(RegistryError::Malformed { path, message: format!("'version' must be at least 1, found {}", version) });
+        }
+
+        if version > REGISTRY_VERSION {
+            return Err(RegistryError::UnsupportedVersion { path, found: version });
+        }
+
+        return table.try_into::<Registry>()
+            .map_err(|err| RegistryError::Malformed { path, message: err.message().to_string() });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Registry, REGISTRY_VERSION};
+    use crate::registry::error::RegistryError;
+    use std::path::PathBuf;
+
+    fn parse(content: &str) -> Result<Registry, RegistryError> {
+        return Registry::parse(PathBuf::from("registry.toml"), content);
+    }
+
+    #[test]
+    fn reads_current_version() {
+        let registry = parse("version = 1\n\n[templates.web]\npath = \"/templates/web\"\n").ok().unwrap();
+
+        assert_eq!(registry.templates["web"].path, PathBuf::from("/templates/web"));
+    }
+
+    #[test]
+    fn refuses_future_version() {
+        let found = parse(&format!("version = {}", REGISTRY_VERSION + 1));
+
+        assert!(matches!(found, Err(RegistryError::UnsupportedVersion { found, .. }) if found == REGISTRY_VERSION + 1));
+    }
+
+    #[test]
+    fn rejects_version_below_one() {
+        assert!(matches!(parse("version = 0"), Err(RegistryError::Malformed { .. })));
+        assert!(matches!(parse("version = -3"), Err(RegistryError::Malformed { .. })));
+    }
+
+    #[test]
+    fn rejects_missing_version() {
+        assert!(matches!(parse("[templates]"), Err(RegistryError::Malformed { .. })));
+    }
+}
@@ -0,0 +1,92 @@
+use crate::registry::error::RegistryError;
+
+pub const NAMESPACE_SEPARATOR: char = '/';
+
+/// Checks that a template name is made of `/` separated segments, like `rust/cli`.
+/// Segments may contain ASCII letters, digits, `.`, `_` and `-`, and must start with a letter or digit.
+pub fn validate(name: &str) -> Result<(), RegistryError> {
+    let invalid = |reason: &str| RegistryError::InvalidName {
+        name: name.to_string(),
+        reason: reason.to_string(),
+    };
+
+    if name.is_empty() {
+        return Err(invalid("the name is empty"));
+    }
+
+    for segment in name.split(NAMESPACE_SEPARATOR) {
+        let Some(first) = segment.chars().next() else {
+            return Err(invalid("namespaces can not be empty, check for leading, trailing or doubled '/'"));
+        };
+
+        if !first.is_ascii_alphanumeric() {
+            return Err(invalid("each segment must start with a letter or a digit"));
+        }
+
+        if let Some(ch) = segment.chars().find(|ch| !ch.is_ascii_alphanumeric() && !matches!(ch, '.' | '_' | '-')) {
+            return Err(invalid(&format!("'{}' is not allowed, use letters, digits, '.', '_', '-' and '/'", ch)));
+        }
+    }
+
+    return Ok(());
+}
+
+/// Whether `name` belongs to `namespace`, either directly or through a nested namespace.
+pub fn in_namespace(name: &str, namespace: &str) -> bool {
+    let namespace = namespace.trim_end_matches(NAMESPACE_SEPARATOR);
+
+    return name.strip_prefix(namespace)
+        .is_some_and(|rest| rest.starts_with(NAMESPACE_SEPARATOR));
+}
+
+/// Whether `query` names the trailing segments of `name`, so `cli` and `rust/cli` both match `rust/cli`.
+pub fn matches_suffix(name: &str, query: &str) -> bool {
+    if name == query {
+        return true;
+    }
+
+    return name.strip_suffix(query)
+        .is_some_and(|rest| rest.ends_with(NAMESPACE_SEPARATOR));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{in_namespace, matches_suffix, validate};
+
+    #[test]
+    fn accepts_namespaced_names() {
+        assert!(validate("rust/cli").is_ok());
+        assert!(validate("web/react-app_2.0").is_ok());
+    }
+
+    #[test]
+    fn rejects_empty_segments() {
+        assert!(validate("").is_err());
+        assert!(validate("/rust").is_err());
+        assert!(validate("rust/").is_err());
+        assert!(validate("rust//cli").is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_characters() {
+        assert!(validate("rust/-cli").is_err());
+        assert!(validate("rust cli").is_err());
+    }
+
+    #[test]
+    fn suffix_matches_whole_segments() {
+        assert!(matches_suffix("rust/cli", "cli"));
+        assert!(matches_suffix("rust/cli", "rust/cli"));
+        assert!(!matches_suffix("rust/cli", "li"));
+        assert!(!matches_suffix("rust/cli", "rust"));
+    }
+
+    #[test]
+    fn namespace_contains_nested_names() {
+        assert!(in_namespace("web/x", "web"));
+        assert!(in_namespace("web/x", "web/"));
+        assert!(in_namespace("web/react/x", "web"));
+        assert!(!in_namespace("webapp/x", "web"));
+        assert!(!in_namespace("web", "web"));
+    }
+}
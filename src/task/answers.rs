@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+
+/// A typed answer to a question declared in `.platenv`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Answer {
+    Integer(i32),
+    Decimal(f32),
+    Text(String),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Integer(number) => write!(f, "{}", number),
+            Answer::Decimal(number) => write!(f, "{}", number),
+            Answer::Text(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+/// The answers given for a load, keyed by the identifier of the question without the `$`.
+/// Answers to `Secret` questions are kept apart so they are never written to disk.
+pub struct Answers {
+    values: BTreeMap<String, Answer>,
+    secrets: BTreeSet<String>,
+}
+
+impl Answers {
+    pub fn new() -> Self {
+        Answers { values: BTreeMap::new(), secrets: BTreeSet::new() }
+    }
+
+    /// Answers recorded by an earlier load, none of which are secret.
+    pub fn from_recorded(values: BTreeMap<String, Answer>) -> Self {
+        Answers { values, secrets: BTreeSet::new() }
+    }
+
+    /// Reads answers from a toml table like `name = "demo"`, as given with `--answers`.
+    pub fn parse(content: &str) -> Result<Answers, String> {
+        let values = toml::from_str(content).map_err(|err| err.message().to_string())?;
+
+        return Ok(Answers::from_recorded(values));
+    }
+
+    pub fn insert(&mut self, identifier: &str, answer: Answer, secret: bool) {
+        if secret {
+            self.secrets.insert(identifier.to_string());
+        } else {
+            self.secrets.remove(identifier);
+        }
+
+        self.values.insert(identifier.to_string(), answer);
+    }
+
+    pub fn get(&self, identifier: &str) -> Option<&Answer> {
+        self.values.get(identifier)
+    }
+
+    /// The answers that may be written to disk, everything except secrets.
+    pub fn recordable(&self) -> BTreeMap<String, Answer> {
+        self.values.iter()
+            .filter(|(identifier, _)| !self.secrets.contains(*identifier))
+            .map(|(identifier, answer)| (identifier.clone(), answer.clone()))
+            .collect()
+    }
+}
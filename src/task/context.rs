@@ -0,0 +1,97 @@
+use crate::task::answers::{Answer, Answers};
+use crate::task::error::Error;
+use crate::task::layers::parsers::commands::StringSource;
+use crate::task::nodes::node::Node;
+use crate::task::position::Position;
+use crate::task::value::string::{StringExpression, StringExpressionPartKind};
+
+/// What a `task.plat` is evaluated against: the typed answers to the questions of `.platenv`.
+pub struct Context {
+    answers: Answers,
+}
+
+impl Context {
+    pub fn new(answers: Answers) -> Self {
+        Context { answers }
+    }
+
+    pub fn answers(&self) -> &Answers {
+        &self.answers
+    }
+
+    pub fn insert(&mut self, identifier: &str, answer: Answer, secret: bool) {
+        self.answers.insert(identifier, answer, secret);
+    }
+
+    /// The answer a variable stands for, `position` is where the variable is used.
+    pub fn get(&self, identifier: &str, position: &Position) -> Result<&Answer, Error> {
+        return self.answers.get(identifier).ok_or_else(|| Error::Evaluation {
+            message: format!("Variable '${}' is not defined", identifier),
+            position: position.clone(),
+        });
+    }
+
+    /// Renders a string source to the text it stands for, answers are inserted in their display form.
+    pub fn render(&self, source: &Node<StringSource>) -> Result<String, Error> {
+        return match &source.data {
+            StringSource::Variable(identifier) => Ok(self.get(identifier, &source.position)?.to_string()),
+            StringSource::Expression(expression) => self.render_expression(expression),
+        };
+    }
+
+    /// Renders a string, an undefined variable is reported at its `$` inside the string.
+    pub fn render_expression(&self, expression: &StringExpression) -> Result<String, Error> {
+        let mut rendered = String::new();
+
+        for part in expression.parts() {
+            match part.kind {
+                StringExpressionPartKind::Literal => rendered.push_str(&part.value),
+                StringExpressionPartKind::Variable => rendered.push_str(&self.get(&part.value, &part.position)?.to_string()),
+            }
+        }
+
+        return Ok(rendered);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Context;
+    use crate::task::answers::{Answer, Answers};
+    use crate::task::error::Error;
+    use crate::task::layers::parsers::commands::{Instruction, StringSource};
+    use crate::task::nodes::collection::NodeCollection;
+    use crate::task::nodes::node::Node;
+    use crate::task::pipeline::parse_task;
+
+    // The `at` string of the second instruction, `$missing` is on line 1 at column 13 counting from 0
+    fn source() -> Node<StringSource<'static>> {
+        let NodeCollection::Ok(instructions) = parse_task("copy at \"a.txt\" to \"out\";\ncopy at \"src/$missing\" to \"x\";") else {
+            panic!("Task does not parse");
+        };
+
+        let Instruction::Copy { source, .. } = &instructions[1].data else {
+            panic!("Second instruction is not a copy");
+        };
+
+        return source[0].clone();
+    }
+
+    #[test]
+    fn undefined_variable_is_reported_at_its_dollar() {
+        let Err(Error::Evaluation { message, position }) = Context::new(Answers::new()).render(&source()) else {
+            panic!("Undefined variable renders");
+        };
+
+        assert_eq!(message, "Variable '$missing' is not defined");
+        assert_eq!((position.line, position.column), (1, 13));
+    }
+
+    #[test]
+    fn defined_variable_is_rendered_in_place() {
+        let mut answers = Answers::new();
+        answers.insert("missing", Answer::Text(String::from("lib")), false);
+
+        assert_eq!(Context::new(answers).render(&source()).ok().as_deref(), Some("src/lib"));
+    }
+}
@@ -19,6 +19,10 @@ pub enum Error {
         message: String,
         position: Position,
     },
+    Evaluation {
+        message: String,
+        position: Position,
+    },
 }
 
 impl Display for Error {
@@ -43,6 +47,13 @@ impl Display for Error {
                 )
             },
 
+            Error::Evaluation { message, position } => {
+                write!(f, "{} at {}",
+                       message,
+                       position
+                )
+            }
+
             Error::Invalid { message, received, position } => {
                 write!(f, "Invalid ({}): Found '{}' at {}",
                        message,
@@ -1,52 +1,468 @@
-// //TODO: Reimplement after handling str_expressions
-// use crate::task::error::Error;
-// use crate::task::layers::parsers::parse_script::Instruction;
-// use crate::task::position::Position;
-// use glob::glob;
-// use std::{fs, net};
-// use crate::task::nodes::Node;
-//
-// pub fn evaluate(nodes: Vec<Node<Instruction>>) -> Option<Vec<Error>> {
-//     let mut errors: Vec<Error> = Vec::new();
-//
-//     for nodes in nodes {
-//         let Node { value, position } = nodes;
-//
-//         match value {
-//             // Instruction::Copy { origin, target } => copy(&origin, &target, position, &mut errors),
-//             _ => {}
-//         }
-//     }
-//
-//     if errors.is_empty() {
-//         None
-//     } else {
-//         Some(errors)
-//     }
-// }
-//
-// // fn copy(origin: &str, target: &str, position: Position, errors: &mut Vec<Error>) {
-// //     let origins = match glob(origin) {
-// //         Ok(paths) => paths.filter_map(Result::ok).collect::<Vec<_>>(),
-// //         Err(err) => {
-// //             errors.push(Error::new(format!("Failed to resolve given origin path: {}", err), position));
-// //             return;
-// //         }
-// //     };
-// //
-// //     let targets = match glob(target) {
-// //         Ok(paths) => paths.filter_map(Result::ok).collect::<Vec<_>>(),
-// //         Err(err) => {
-// //             errors.push(Error::new(format!("Failed to resolve given target path: {}", err), position));
-// //             return;
-// //         }
-// //     };
-// //
-// //     for origin in origins {
-// //         for target in &targets {
-// //             if let Err(err) = fs::copy(&origin, target) {
-// //                 errors.push(Error::new(format!("Failed to copy file: {}", err), position.clone()));
-// //             }
-// //         }
-// //     }
-// // }
+use crate::load::conflict::ConflictPolicy;
+use crate::load::copy::CopyOptions;
+use crate::load::ignore::IgnoreRules;
+use crate::load::lockfile;
+use crate::load::resolve;
+use crate::load::plan::{Action, Plan};
+use crate::task::context::Context;
+use crate::task::error::Error;
+use crate::task::layers::parsers::commands::{Instruction, StringSource};
+use crate::task::nodes::node::Node;
+use crate::task::position::Position;
+use glob::{glob_with, MatchOptions, Pattern};
+use regex::{NoExpand, Regex};
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Where the paths of a `task.plat` are resolved: `at` globs against the template root,
+/// `to` paths against the load target. Variables in them are rendered with `context`.
+pub struct Scope<'a> {
+    pub origin: &'a Path,
+    pub target: &'a Path,
+    pub context: &'a Context,
+    pub rules: &'a IgnoreRules,
+    pub copy: &'a CopyOptions,
+    /// The files of the target the last load rewrote in place, as keys of its lockfile.
+    pub edited: &'a BTreeSet<String>,
+}
+
+/// The number of replacements a `write` made in a file, `path` is relative to the load target.
+pub struct Rewrite {
+    pub path: PathBuf,
+    pub count: usize,
+}
+
+impl Display for Rewrite {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: replaced {} match(es)", self.path.display(), self.count)
+    }
+}
+
+/// Evaluates the instructions of a `task.plat` into steps of `plan`, nothing is written until the plan is applied.
+/// Every instruction is evaluated even after one fails, so all errors are reported together.
+/// Returns the replacements each `write` made, in the order they were made.
+pub fn evaluate(instructions: &[Node<Instruction>], scope: &Scope, plan: &mut Plan) -> Result<Vec<Rewrite>, Vec<Error>> {
+    let mut rewrites = Vec::new();
+    let mut errors = Vec::new();
+
+    for Node { data, position } in instructions {
+        let result = match data {
+            Instruction::Copy { source, target, conflict } => copy(source, target, *conflict, position, scope, plan),
+            Instruction::Write { value, selector, target, conflict } => {
+                write(value, selector, target, *conflict, position, scope, plan).map(|done| rewrites.extend(done))
+            }
+        };
+
+        if let Err(err) = result {
+            errors.push(err);
+        }
+    }
+
+    if errors.is_empty() {
+        return Ok(rewrites);
+    }
+
+    return Err(errors);
+}
+
+/// Copies everything the `source` globs match to each of the `target` paths.
+/// Matches the template's ignore rules exclude are left out. With a single source without glob characters the match
+/// is copied to the target path itself, a directory's contents are copied into it. With several sources, a glob,
+/// or a target ending in `/`, the target is a directory and each match is copied into it by name.
+fn copy(source: &[Node<StringSource>], target: &[Node<StringSource>], conflict: Option<ConflictPolicy>, position: &Position, scope: &Scope, plan: &mut Plan) -> Result<(), Error> {
+    let mut matches = Vec::new();
+    let mut several = source.len() > 1;
+
+    let origin = resolve(scope.origin);
+
+    for source in source {
+        let pattern = scope.context.render(source)?;
+
+        let Some(relative) = contained(&pattern) else {
+            return Err(outside(source, &pattern, "template"));
+        };
+
+        // Whether the pattern is a glob decides the shape of the output, not how many files it happens to match
+        several |= pattern.contains(['*', '?', '[']);
+
+        let mut found = expand(scope.origin, &relative.to_string_lossy(), position)?;
+
+        found.retain(|path| !scope.rules.is_ignored(path, scope.origin.join(path).is_dir()));
+
+        if found.is_empty() {
+            return Err(Error::Evaluation { message: format!("'{}' does not match any file in the template that is not ignored", pattern), position: position.clone() });
+        }
+
+        // A match can still lead out of the template through a symlink
+        for path in &found {
+            let resolved = resolve(&scope.origin.join(path));
+
+            if !resolved.starts_with(&origin) {
+                return Err(outside(source, &resolved.to_string_lossy(), "template"));
+            }
+        }
+
+        matches.extend(found);
+    }
+
+    for target in target {
+        let rendered = scope.context.render(target)?;
+        let into_directory = several || rendered.ends_with('/');
+        let root = confine(target, &rendered, scope)?;
+
+        for relative in &matches {
+            let destination = match (into_directory, relative.file_name()) {
+                (true, Some(name)) => root.join(name),
+                _ => root.clone(),
+            };
+
+            // The root of the target already exists, any other directory is created first
+            let result = if scope.origin.join(relative).is_dir() && !into_directory {
+                if destination.components().next().is_some() {
+                    plan.push(destination.clone(), Action::CreateDirectory, conflict);
+                }
+
+                plan.add_directory(scope.origin, relative, &destination, scope.rules, scope.copy.symlinks, conflict)
+            } else {
+                plan.add_entry(scope.origin, relative, destination, scope.rules, scope.copy.symlinks, conflict)
+            };
+
+            result.map_err(|err| Error::Evaluation { message: err.to_string(), position: position.clone() })?;
+        }
+
+        // Either side can be new, a link planned over steps that came before it is as bad as steps below an earlier link
+        let links = planned_links(plan);
+
+        for step in &plan.steps {
+            if let Some(link) = below_link(&links, &step.path) {
+                return Err(through_link(target, &step.path, link));
+            }
+        }
+    }
+
+    return Ok(());
+}
+
+/// Replaces every match of the `selector` regex in each of the `target` files with `value`.
+/// A file the plan already writes is rewritten in the plan, any other file is read from the load target and rewritten in place.
+/// A file the last load already rewrote in place may no longer match, it is then left as it is.
+fn write(value: &Node<StringSource>, selector: &str, target: &[Node<StringSource>], conflict: Option<ConflictPolicy>, position: &Position, scope: &Scope, plan: &mut Plan) -> Result<Vec<Rewrite>, Error> {
+    let error = |message: String| Error::Evaluation { message, position: position.clone() };
+
+    // Syntax errors come with the pattern and a caret above the message, only the message fits on one line
+    let regex = Regex::new(selector).map_err(|err| {
+        let message = err.to_string();
+        let reason = message.lines().last().unwrap_or_default().trim_start_matches("error: ").to_string();
+
+        error(format!("/{}/ is not a valid regex, {}", selector, reason))
+    })?;
+
+    let value = scope.context.render(value)?;
+    let mut rewrites = Vec::new();
+
+    for target in target {
+        let path = confine(target, &scope.context.render(target)?, scope)?;
+
+        if let Some(link) = below_link(&planned_links(plan), &path) {
+            return Err(through_link(target, &path, link));
+        }
+
+        // The last step for a path decides what ends up there, so that is the one to rewrite
+        let planned = plan.steps.iter().rposition(|step| step.path == path);
+
+        let (source, content, in_place) = match planned.map(|index| &plan.steps[index].action) {
+            Some(Action::CreateDirectory) => return Err(error(format!("Can not write to '{}', it is a directory", path.display()))),
+            Some(Action::Symlink(_)) => return Err(error(format!("Can not write to '{}', it is a symlink", path.display()))),
+
+            Some(Action::Edit { path, content }) => (path.clone(), content.clone(), true),
+
+            Some(action) => {
+                let content = action.read_content()
+                    .map_err(|err| error(format!("Failed to read '{}': {}", action.source().display(), err)))?;
+
+                (action.source(), content, false)
+            }
+
+            None => {
+                let existing = scope.target.join(&path);
+
+                if !existing.is_file() {
+                    return Err(error(format!("Can not write to '{}', the template does not create it and it does not exist in the target", path.display())));
+                }
+
+                let content = fs::read(&existing)
+                    .map_err(|err| error(format!("Failed to read '{}': {}", existing.display(), err)))?;
+
+                (existing, content, true)
+            }
+        };
+
+        let Ok(text) = String::from_utf8(content) else {
+            return Err(error(format!("Can not write to '{}', it is not a text file", path.display())));
+        };
+
+        let count = regex.find_iter(&text).count();
+
+        if count == 0 && !(in_place && scope.edited.contains(&lockfile::file_key(&path))) {
+            return Err(error(format!("/{}/ does not match anything in '{}'", selector, path.display())));
+        }
+
+        // The value is inserted as it is, `$` in it does not refer to capture groups
+        let content = regex.replace_all(&text, NoExpand(&value)).into_owned().into_bytes();
+
+        let action = match in_place {
+            true => Action::Edit { path: source, content },
+            false => Action::Generate { source, content },
+        };
+
+        match planned {
+            Some(index) => {
+                let step = &mut plan.steps[index];
+
+                step.action = action;
+                step.conflict = conflict.or(step.conflict);
+            }
+
+            None => plan.push(path.clone(), action, conflict),
+        }
+
+        rewrites.push(Rewrite { path, count });
+    }
+
+    return Ok(rewrites);
+}
+
+/// The paths matching `pattern` relative to `origin`, sorted, themselves relative to `origin`.
+fn expand(origin: &Path, pattern: &str, position: &Position) -> Result<Vec<PathBuf>, Error> {
+    let full = format!("{}/{}", Pattern::escape(&origin.to_string_lossy()), pattern.trim_start_matches("./"));
+
+    let options = MatchOptions {
+        case_sensitive: true,
+        require_literal_separator: true,
+        require_literal_leading_dot: false,
+    };
+
+    let paths = glob_with(&full, options)
+        .map_err(|err| Error::Evaluation { message: format!("'{}' is not a valid glob, {}", pattern, err.msg), position: position.clone() })?;
+
+    let mut found = Vec::new();
+
+    for path in paths {
+        let path = path
+            .map_err(|err| Error::Evaluation { message: format!("Failed to read '{}': {}", err.path().display(), err.error()), position: position.clone() })?;
+
+        found.push(path.strip_prefix(origin).map(Path::to_path_buf).unwrap_or(path));
+    }
+
+    return Ok(found);
+}
+
+/// The path `rendered` stands for inside the load target. It is rejected when it leaves the target,
+/// either with `..` or an absolute path, or through a symlink that is already in the target.
+fn confine(target: &Node<StringSource>, rendered: &str, scope: &Scope) -> Result<PathBuf, Error> {
+    let Some(path) = contained(rendered) else {
+        return Err(outside(target, rendered, "load target"));
+    };
+
+    let resolved = resolve(&scope.target.join(&path));
+
+    if !resolved.starts_with(resolve(scope.target)) {
+        return Err(outside(target, &resolved.to_string_lossy(), "load target"));
+    }
+
+    return Ok(path);
+}
+
+// The symlinks the plan creates. `confine` only sees the target as it is before the load, so anything planned below
+// one of these would be written wherever the link points once it exists
+fn planned_links(plan: &Plan) -> Vec<PathBuf> {
+    plan.steps.iter()
+        .filter(|step| matches!(step.action, Action::Symlink(_)))
+        .map(|step| step.path.clone())
+        .collect()
+}
+
+fn below_link<'l>(links: &'l [PathBuf], path: &Path) -> Option<&'l PathBuf> {
+    links.iter().find(|link| path != link.as_path() && path.starts_with(link))
+}
+
+fn through_link(target: &Node<StringSource>, path: &Path, link: &Path) -> Error {
+    Error::Evaluation {
+        message: format!("{} leads to '{}' through '{}', a symlink the template creates", target.data, path.display(), link.display()),
+        position: target.position.clone(),
+    }
+}
+
+// Works out `.` and `..` without touching the disk, so `./src//main.rs` and `src/main.rs` are the same step.
+// None when the path is absolute or climbs above where it starts
+fn contained(rendered: &str) -> Option<PathBuf> {
+    let mut path = PathBuf::new();
+
+    for component in Path::new(rendered).components() {
+        match component {
+            Component::CurDir => {}
+            Component::Normal(name) => path.push(name),
+            Component::ParentDir if path.pop() => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+
+    return Some(path);
+}
+
+// Names the string a rejected path came from along with what it resolved to
+fn outside(source: &Node<StringSource>, resolved: &str, place: &str) -> Error {
+    Error::Evaluation {
+        message: format!("{} resolves to '{}', which is outside of the {}", source.data, resolved, place),
+        position: source.position.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::load::build_plan;
+    use crate::load::copy::{CopyOptions, SymlinkPolicy};
+    use crate::load::plan::{Action, Plan};
+    use crate::task::answers::Answers;
+    use crate::task::context::Context;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    // A fresh template and target directory for each test, named after it so tests can run in parallel
+    fn directories(name: &str, task: &str) -> (PathBuf, PathBuf) {
+        let root = std::env::temp_dir().join(format!("plat-evaluate-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&root);
+
+        let origin = root.join("template");
+        let target = root.join("target");
+
+        fs::create_dir_all(&origin).expect("Create template directory");
+        fs::create_dir_all(&target).expect("Create target directory");
+        fs::write(origin.join("task.plat"), task).expect("Write task file");
+
+        return (origin, target);
+    }
+
+    fn file(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).expect("Create parent directory");
+        fs::write(path, content).expect("Write test file");
+    }
+
+    fn plan(origin: &Path, target: &Path, copy: &CopyOptions) -> Result<Plan, String> {
+        return build_plan(origin, target, &Context::new(Answers::new()), copy)
+            .map(|(plan, _)| plan)
+            .map_err(|err| err.to_string());
+    }
+
+    fn paths(plan: &Plan) -> Vec<String> {
+        return plan.steps.iter().map(|step| step.path.to_string_lossy().into_owned()).collect();
+    }
+
+    #[test]
+    fn glob_leaves_out_ignored_files() {
+        let (origin, target) = directories("ignored", "copy at \"*\" to \"./\";");
+        file(&origin.join(".git/HEAD"), "ref");
+        file(&origin.join("readme.md"), "hello");
+
+        assert_eq!(paths(&plan(&origin, &target, &CopyOptions::default()).unwrap()), ["readme.md"]);
+    }
+
+    #[test]
+    fn glob_with_one_match_copies_into_target() {
+        let (origin, target) = directories("single-glob", "copy at \"src/*.rs\" to \"lib\";");
+        file(&origin.join("src/main.rs"), "fn main() {}");
+
+        assert_eq!(paths(&plan(&origin, &target, &CopyOptions::default()).unwrap()), ["lib/main.rs"]);
+    }
+
+    #[test]
+    fn literal_source_copies_to_target() {
+        let (origin, target) = directories("literal", "copy at \"src/main.rs\" to \"lib.rs\";");
+        file(&origin.join("src/main.rs"), "fn main() {}");
+
+        assert_eq!(paths(&plan(&origin, &target, &CopyOptions::default()).unwrap()), ["lib.rs"]);
+    }
+
+    #[test]
+    fn write_to_target_file_edits_in_place() {
+        let (origin, target) = directories("edit", "write \"plat\" for /world/ to \"foo.txt\";");
+        file(&target.join("foo.txt"), "hello world");
+
+        let plan = plan(&origin, &target, &CopyOptions::default()).unwrap();
+
+        assert!(matches!(&plan.steps[0].action, Action::Edit { content, .. } if content == b"hello plat"));
+    }
+
+    #[test]
+    fn write_over_earlier_edit_needs_no_match() {
+        let (origin, target) = directories("edited", "write \"plat\" for /world/ to \"foo.txt\";");
+        file(&target.join("foo.txt"), "hello plat");
+
+        assert!(plan(&origin, &target, &CopyOptions::default()).err().unwrap().contains("does not match anything"));
+
+        file(&target.join(".plat-lock"), "template = \"t\"\ntemplate-hash = \"\"\nplat-version = \"0.1.0\"\ngenerated = \"\"\nedited = [\"foo.txt\"]\n");
+
+        let plan = plan(&origin, &target, &CopyOptions::default()).unwrap();
+
+        assert!(matches!(&plan.steps[0].action, Action::Edit { content, .. } if content == b"hello plat"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_copy_through_planned_symlink() {
+        let (origin, target) = directories("planned-link", "copy at \"d\" to \"dir\";\ncopy at \"payload\" to \"dir/x/owned.txt\";");
+        let victim = origin.parent().unwrap().join("victim");
+
+        fs::create_dir_all(origin.join("d")).expect("Create template directory");
+        fs::create_dir_all(&victim).expect("Create victim directory");
+        std::os::unix::fs::symlink(&victim, origin.join("d/x")).expect("Create symlink");
+        file(&origin.join("payload"), "owned");
+
+        assert!(plan(&origin, &target, &CopyOptions::default()).err().unwrap().contains("a symlink the template creates"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_write_through_planned_symlink() {
+        let (origin, target) = directories("planned-link-write", "copy at \"d\" to \"dir\";\nwrite \"owned\" for /a/ to \"dir/x/file\";");
+        let victim = origin.parent().unwrap().join("victim");
+
+        fs::create_dir_all(origin.join("d")).expect("Create template directory");
+        file(&victim.join("file"), "a");
+        std::os::unix::fs::symlink(&victim, origin.join("d/x")).expect("Create symlink");
+
+        assert!(plan(&origin, &target, &CopyOptions::default()).err().unwrap().contains("a symlink the template creates"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_followed_symlink_outside_template() {
+        let (origin, target) = directories("follow-outside", "copy at \"src\" to \"src\";");
+        let secret = origin.parent().unwrap().join("secret");
+
+        file(&origin.join("src/main.rs"), "fn main() {}");
+        file(&secret, "secret");
+        std::os::unix::fs::symlink(&secret, origin.join("src/leak")).expect("Create symlink");
+
+        let follow = CopyOptions { symlinks: SymlinkPolicy::Follow, preserve_mtime: false };
+
+        assert!(plan(&origin, &target, &follow).err().unwrap().contains("outside of the template"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn follows_symlink_inside_template() {
+        let (origin, target) = directories("follow-inside", "copy at \"src\" to \"src\";");
+
+        file(&origin.join("src/main.rs"), "fn main() {}");
+        std::os::unix::fs::symlink(origin.join("src/main.rs"), origin.join("src/alias.rs")).expect("Create symlink");
+
+        let follow = CopyOptions { symlinks: SymlinkPolicy::Follow, preserve_mtime: false };
+        let plan = plan(&origin, &target, &follow).unwrap();
+
+        assert_eq!(paths(&plan), ["src", "src/alias.rs", "src/main.rs"]);
+        assert!(matches!(plan.steps[1].action, Action::Copy(_)));
+    }
+}
@@ -27,7 +27,7 @@ struct Cursor<'a> {
 }
 
 impl<'a> Cursor<'a> {
-    fn new(data: &'a str) -> Cursor {
+    fn new(data: &'a str) -> Cursor<'a> {
         Cursor {
             data,
             head: 0,
@@ -58,7 +58,7 @@ struct Iteration<'a> {
     current: Option<char>
 }
 impl Iteration<'_> {
-    fn new(data: &str) -> Iteration {
+    fn new(data: &str) -> Iteration<'_> {
         let mut iterator = data.chars();
         let current = iterator.next();
 
@@ -73,6 +73,10 @@ impl Iteration<'_> {
         self.current = next;
     }
 
+    fn peek(&self) -> Option<char> {
+        self.iterator.clone().next()
+    }
+
     fn advance(&mut self, ch: char) {
         self.next();
 
@@ -84,7 +88,7 @@ impl Iteration<'_> {
     }
 }
 
-pub fn fragmentize(data: &str) -> Vec<Node<Fragment>> {
+pub fn fragmentize(data: &str) -> Vec<Node<Fragment<'_>>> {
     let mut fragments: Vec<Node<Fragment>> = Vec::new();
 
     let mut iteration = Iteration::new(data);
@@ -98,12 +102,15 @@ pub fn fragmentize(data: &str) -> Vec<Node<Fragment>> {
             let pos = iteration.position.clone();
             alphanumeric(&mut fragments, &mut iteration, &mut cursor, pos);
         } else {
-            if ch == '-' {
+            // A '-' is only the sign of a number when a digit follows, like in `-5`, otherwise it is a symbol as in `[a-z]`
+            if ch == '-' && iteration.peek().is_some_and(|next| next.is_numeric()) {
                 let pos = iteration.position.clone();
 
                 cursor.take();
                 iteration.advance(ch);
                 numeric(&mut fragments, &mut iteration, &mut cursor, pos);
+
+                continue;
             } else if !ch.is_whitespace() {
                 fragments.push(Node::new(
                     Fragment::Symbol(ch),
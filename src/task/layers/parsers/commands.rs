@@ -1,6 +1,8 @@
+use std::fmt::{Display, Formatter};
 use std::sync::Arc;
 use std::thread::yield_now;
 use crate::{expect_node, expect_node_optional, node, some_node};
+use crate::load::conflict::{ConflictPolicy, CONFLICT_POLICIES};
 use crate::task::error::Error;
 use crate::task::layers::tokenize::Token;
 use crate::task::nodes::collection::NodeCollection;
@@ -25,30 +27,42 @@ pub enum StringSource<'a> {
     Expression(StringExpression),
 }
 
+impl Display for StringSource<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StringSource::Variable(identifier) => write!(f, "${}", identifier),
+            StringSource::Expression(expression) => write!(f, "{}", expression),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum Modifier<'a> {
-    At(Vec<StringSource<'a>>),
-    To(Vec<StringSource<'a>>),
+    At(Vec<Node<StringSource<'a>>>),
+    To(Vec<Node<StringSource<'a>>>),
     For(String),
+    Conflict(ConflictPolicy),
 }
 
 #[derive(Clone)]
 pub enum Command<'a> {
     Copy(Position),
-    Write(StringSource<'a>, Position),
+    Write(Node<StringSource<'a>>, Position),
 }
 
 
 #[derive(Debug)]
 pub enum Instruction<'a> {
     Copy {
-        source: Box<[StringSource<'a>]>,
-        target: Box<[StringSource<'a>]>,
+        source: Box<[Node<StringSource<'a>>]>,
+        target: Box<[Node<StringSource<'a>>]>,
+        conflict: Option<ConflictPolicy>,
     },
     Write {
-        value: StringSource<'a>,
+        value: Node<StringSource<'a>>,
         selector: String,
-        target: Box<[StringSource<'a>]>,
+        target: Box<[Node<StringSource<'a>>]>,
+        conflict: Option<ConflictPolicy>,
     },
 }
 
@@ -127,6 +141,7 @@ fn keyword<'a>(iter: &mut NodeIter<Token<'a>>, collection: &mut NodeCollection<I
             "at" => at_modifier(iter, collection, stack),
             "to" => to_modifier(iter, collection, stack),
             "for" => for_modifier(iter, collection, stack, position),
+            "conflict" => conflict_modifier(iter, collection, stack, position),
             "copy" => copy(collection, command, position),
             "write" => write(iter, collection, command, position),
 
@@ -141,10 +156,10 @@ fn keyword<'a>(iter: &mut NodeIter<Token<'a>>, collection: &mut NodeCollection<I
     }).map_err(|err| collection.throw(err));
 }
 
-fn string_param<'a>(iter: &mut NodeIter<Token<'a>>, collection: &mut NodeCollection<Instruction<'a>>) -> Result<StringSource<'a>, Error> {
+fn string_param<'a>(iter: &mut NodeIter<Token<'a>>, collection: &mut NodeCollection<Instruction<'a>>) -> Result<Node<StringSource<'a>>, Error> {
     expect_node!(iter.next(), "String, Identifier or Scope",
-        some_node!(Token::Value(Value::String(expr))) => StringSource::Expression(expr),
-        some_node!(Token::Identifier(identifier)) => StringSource::Variable(identifier)
+        some_node!(Token::Value(Value::String(expr)), position) => Node::new(StringSource::Expression(expr), position),
+        some_node!(Token::Identifier(identifier), position) => Node::new(StringSource::Variable(identifier), position)
     )
 }
 
@@ -187,6 +202,30 @@ fn for_modifier<'a>(iter: &mut NodeIter<Token<'a>>, collection: &mut NodeCollect
     }
 }
 
+fn conflict_modifier<'a>(iter: &mut NodeIter<Token<'a>>, collection: &mut NodeCollection<Instruction<'a>>, stack: &mut Vec<Modifier<'a>>, position: Position) {
+    let (name, name_position) = guard!(expect_node!(iter.next(), "Conflict policy", some_node!(Token::Segment(name), name_position) => (name, name_position))
+        .map_err(|err| collection.throw(err)));
+
+    let Some(policy) = ConflictPolicy::parse(name) else {
+        collection.throw(Error::Invalid {
+            message: format!("Conflict policy must be one of {}", CONFLICT_POLICIES),
+            received: name.to_string(),
+            position: name_position,
+        });
+        return;
+    };
+
+    if stack.iter().any(|modifier| matches!(modifier, Modifier::Conflict(_))) {
+        collection.throw(Error::Invalid {
+            message: String::from("Conflict modifier can not be chained multiple times."),
+            received: String::from("conflict"),
+            position,
+        });
+    } else {
+        stack.push(Modifier::Conflict(policy));
+    }
+}
+
 fn copy<'a>(collection: &mut NodeCollection<Instruction<'a>>, command: &mut Option<Command<'a>>, position: Position) {
     match command {
         Some(_) => collection.throw(Error::Other {
@@ -225,16 +264,18 @@ fn submit_stack<'a>(collection: &mut NodeCollection<Instruction<'a>>, stack: Vec
 
     match command {
         Command::Copy(position) => {
-            let mut source: Option<Box<[StringSource]>> = None;
-            let mut target: Option<Box<[StringSource]>> = None;
+            let mut source: Option<Box<[Node<StringSource>]>> = None;
+            let mut target: Option<Box<[Node<StringSource>]>> = None;
+            let mut conflict: Option<ConflictPolicy> = None;
 
             for modifier in stack {
                 match modifier {
                     Modifier::At(vec) => source = Some(vec.into_boxed_slice()),
                     Modifier::To(vec) => target = Some(vec.into_boxed_slice()),
+                    Modifier::Conflict(policy) => conflict = Some(policy),
 
                     _ => collection.throw(Error::Other {
-                        message: String::from("Copy command can only be used under 'at' and 'to' modifiers."),
+                        message: String::from("Copy command can only be used under 'at', 'to' and 'conflict' modifiers."),
                         position: position.clone(),
                     })
                 }
@@ -259,22 +300,24 @@ fn submit_stack<'a>(collection: &mut NodeCollection<Instruction<'a>>, stack: Vec
             };
 
             collection.try_push(|| Node::new(
-                Instruction::Copy { source, target },
+                Instruction::Copy { source, target, conflict },
                 position.clone(),
             ));
         }
 
         Command::Write(value, position) => {
             let mut selector: Option<String> = None;
-            let mut target: Option<Box<[StringSource]>> = None;
+            let mut target: Option<Box<[Node<StringSource>]>> = None;
+            let mut conflict: Option<ConflictPolicy> = None;
 
             for modifier in stack {
                 match modifier {
                     Modifier::For(str) => selector = Some(str),
                     Modifier::To(vec) => target = Some(vec.into_boxed_slice()),
+                    Modifier::Conflict(policy) => conflict = Some(policy),
 
                     _ => collection.throw(Error::Other {
-                        message: String::from("Write command can only be used under 'for' and 'to' modifiers."),
+                        message: String::from("Write command can only be used under 'for', 'to' and 'conflict' modifiers."),
                         position: position.clone(),
                     })
                 }
@@ -299,7 +342,7 @@ fn submit_stack<'a>(collection: &mut NodeCollection<Instruction<'a>>, stack: Vec
             };
 
             collection.try_push(|| Node::new(
-                Instruction::Write { value, selector, target },
+                Instruction::Write { value, selector, target, conflict },
                 position.clone(),
             ));
         }
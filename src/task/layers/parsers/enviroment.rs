@@ -5,6 +5,7 @@ use crate::task::nodes::collection::NodeCollection;
 use crate::task::nodes::iterator::NodeIter;
 use crate::task::nodes::node::Node;
 use crate::task::position::Position;
+use crate::task::value::number::NumberValue;
 use crate::task::value::range::RangeValue;
 use crate::task::value::string::StringExpression;
 use crate::task::value::{Value, ALL_VALUES};
@@ -20,6 +21,7 @@ pub enum FieldType {
     Integer,
     Decimal,
     Switch,
+    Secret,
 }
 
 #[derive(Debug)]
@@ -38,8 +40,8 @@ pub enum MatchPattern<'a> {
 
 #[derive(Debug)]
 pub struct MatchCase<'a> {
-    patterns: Box<[MatchPattern<'a>]>,
-    expression: Expression<'a>,
+    pub patterns: Box<[MatchPattern<'a>]>,
+    pub expression: Expression<'a>,
 }
 
 #[derive(Debug)]
@@ -56,6 +58,48 @@ pub enum Statement<'a> {
     },
 }
 
+impl Display for FieldType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FieldType::Text => write!(f, "Text"),
+            FieldType::Integer => write!(f, "Integer"),
+            FieldType::Decimal => write!(f, "Decimal"),
+            FieldType::Switch => write!(f, "Switch"),
+            FieldType::Secret => write!(f, "Secret"),
+        }
+    }
+}
+
+impl Display for Validator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Validator::Range(begin, end) => write!(f, "{}..{}", begin, end),
+            Validator::Regex(regex) => write!(f, "/{}/", regex),
+            Validator::Switch(options) => {
+                let options = options.iter()
+                    .map(|option| format!("{}", option))
+                    .collect::<Vec<_>>();
+
+                write!(f, "[{}]", options.join(", "))
+            }
+        }
+    }
+}
+
+impl Display for MatchPattern<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MatchPattern::Any => write!(f, "*"),
+            MatchPattern::Variable(identifier) => write!(f, "${}", identifier),
+            MatchPattern::Value(Value::Range(RangeValue(begin, end))) => write!(f, "{}..{}", begin, end),
+            MatchPattern::Value(Value::String(expr)) => write!(f, "{}", expr),
+            MatchPattern::Value(Value::Number(NumberValue::Integer(number))) => write!(f, "{}", number),
+            MatchPattern::Value(Value::Number(NumberValue::Decimal(number))) => write!(f, "{}", number),
+            MatchPattern::Value(value) => write!(f, "{}", value),
+        }
+    }
+}
+
 pub fn parse_enviroment(tokens: Vec<Node<Token>>) -> NodeCollection<Statement> {
     let mut iter = NodeIter::new(tokens);
     let mut collection = NodeCollection::new();
@@ -206,8 +250,9 @@ fn field_declaration_statement<'a>(iter: &mut NodeIter<Token>, collection: &mut
         "Integer" => FieldType::Integer,
         "Decimal" => FieldType::Decimal,
         "Switch" => FieldType::Switch,
+        "Secret" => FieldType::Secret,
         _ => {
-            collection.throw(Error::Other { message: String::from("Text, Integer, Decimal or Switch"), position });
+            collection.throw(Error::Other { message: String::from("Text, Integer, Decimal, Switch or Secret"), position });
             return;
         }
     };
@@ -147,11 +147,16 @@ fn capture_regex<'a>(iter: &mut NodeIter<Fragment<'a>>) -> Result<Token<'a>, Err
 
 fn capture_string<'a>(iter: &mut NodeIter<Fragment<'a>>) -> Result<Token<'a>, Error> {
     let mut buf = String::new();
+    let mut start = iter.position.clone();
     let mut expr: StringExpression = StringExpression::new();
 
     let mut last_was_symbol = true;
 
     while let Some(fragment) = iter.peek() {
+        if buf.is_empty() {
+            start = fragment.position.clone();
+        }
+
         match fragment.data {
             Fragment::Numeric(slice) |
             Fragment::AlphaNumeric(slice) => {
@@ -186,7 +191,8 @@ fn capture_string<'a>(iter: &mut NodeIter<Fragment<'a>>) -> Result<Token<'a>, Er
                         if !buf.is_empty() {
                             expr.push(StringExpressionPart {
                                 kind: StringExpressionPartKind::Literal,
-                                value: buf.clone()
+                                value: buf.clone(),
+                                position: start,
                             });
                         }
 
@@ -194,12 +200,15 @@ fn capture_string<'a>(iter: &mut NodeIter<Fragment<'a>>) -> Result<Token<'a>, Er
                     }
 
                     '$' => {
+                        let position = fragment.position.clone();
+
                         iter.skip();
                         
                         if !buf.is_empty() {
                             expr.push(StringExpressionPart {
                                 kind: StringExpressionPartKind::Literal,
-                                value: buf.clone()
+                                value: buf.clone(),
+                                position: start.clone(),
                             });
                         }
 
@@ -209,7 +218,8 @@ fn capture_string<'a>(iter: &mut NodeIter<Fragment<'a>>) -> Result<Token<'a>, Er
 
                                 expr.push(StringExpressionPart {
                                     kind: StringExpressionPartKind::Variable,
-                                    value: String::from(slice)
+                                    value: String::from(slice),
+                                    position,
                                 });
                                 
                                 buf.clear();
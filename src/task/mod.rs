@@ -3,3 +3,6 @@ pub mod error;
 pub mod value;
 pub mod layers;
 pub mod nodes;
+pub mod pipeline;
+pub mod answers;
+pub mod context;
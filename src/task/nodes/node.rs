@@ -76,6 +76,15 @@ impl<T> Node<T> {
     }
 }
 
+impl<T> Clone for Node<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        Node { data: self.data.clone(), position: self.position.clone() }
+    }
+}
+
 impl<T> Debug for Node<T>
 where
     T: Debug,
@@ -0,0 +1,24 @@
+use crate::task::layers::fragmentize::fragmentize;
+use crate::task::layers::parsers::commands::{parse_commands, Instruction};
+use crate::task::layers::parsers::enviroment::{parse_enviroment, Statement};
+use crate::task::layers::tokenize::tokenize;
+use crate::task::nodes::collection::NodeCollection;
+
+pub const TASK_FILE_NAME: &str = "task.plat";
+pub const ENVIROMENT_FILE_NAME: &str = ".platenv";
+
+/// Runs the fragmentize, tokenize and parse layers over the content of a `task.plat` file.
+pub fn parse_task(content: &str) -> NodeCollection<Instruction<'_>> {
+    match tokenize(fragmentize(content)) {
+        NodeCollection::Ok(tokens) => parse_commands(tokens),
+        NodeCollection::Failed(errors) => NodeCollection::Failed(errors),
+    }
+}
+
+/// Runs the fragmentize, tokenize and parse layers over the content of a `.platenv` file.
+pub fn parse_enviroment_file(content: &str) -> NodeCollection<Statement<'_>> {
+    match tokenize(fragmentize(content)) {
+        NodeCollection::Ok(tokens) => parse_enviroment(tokens),
+        NodeCollection::Failed(errors) => NodeCollection::Failed(errors),
+    }
+}
@@ -11,6 +11,12 @@ impl Display for Position {
     }
 }
 
+impl Debug for Position {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
 impl Clone for Position {
     fn clone(&self) -> Self {
         return Position { line: self.line, column: self.column };
@@ -2,6 +2,7 @@ use std::fmt::{write, Display, Formatter};
 use std::str::{from_utf8, Utf8Error};
 use std::sync::Arc;
 use crate::task::layers::parsers::commands::StringSource;
+use crate::task::position::Position;
 
 #[derive(Debug, Clone)]
 pub struct StringExpression {
@@ -18,6 +19,7 @@ pub enum StringExpressionPartKind {
 pub struct StringExpressionPart {
     pub kind: StringExpressionPartKind,
     pub value: String,
+    pub position: Position,
 }
 
 impl Display for StringExpression {
@@ -43,5 +45,9 @@ impl StringExpression {
     pub fn push(&mut self, part: StringExpressionPart) {
         self.parts.push(part);
     }
+
+    pub fn parts(&self) -> &[StringExpressionPart] {
+        &self.parts
+    }
 }
 
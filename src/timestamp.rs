@@ -0,0 +1,37 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Formats the current time as an RFC 3339 UTC timestamp, e.g. `2024-08-01T12:30:00Z`.
+pub fn now() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+
+    return format_unix(seconds);
+}
+
+pub fn format_unix(seconds: u64) -> String {
+    let days = (seconds / 86400) as i64;
+    let time = seconds % 86400;
+
+    let (year, month, day) = civil_from_days(days);
+
+    return format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+                   year, month, day,
+                   time / 3600, time % 3600 / 60, time % 60
+    );
+}
+
+// Howard Hinnant's days-to-civil algorithm, valid for the whole proleptic Gregorian calendar
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    return (year, month, day);
+}